@@ -1,6 +1,8 @@
 use sdl2;
 use sdl2::audio::{AudioQueue, AudioSpecDesired};
 
+use rgb::AudioSink;
+
 pub const SPEC_SAMPLE_RATE: i32 = 44100;
 pub const SPEC_CHANNELS: u8 = 2;
 pub const SPEC_SAMPLES: u16 = 4096;
@@ -26,26 +28,27 @@ impl AudioSystem {
 
         AudioSystem {
             buffer: Vec::new(),
-            device: device,
+            device,
 
             paused: true,
         }
     }
+}
 
-    pub fn pause(&mut self) {
+impl AudioSink for AudioSystem {
+    fn pause(&mut self) {
         self.device.pause();
         self.buffer.clear();
 
         self.paused = true;
-
     }
 
-    pub fn resume(&mut self) {
+    fn resume(&mut self) {
         self.device.resume();
         self.paused = false;
     }
 
-    pub fn add_samples(&mut self, samples: &[i16]) {
+    fn add_samples(&mut self, samples: &[i16]) {
         if self.paused {
             return;
         }
@@ -59,4 +62,4 @@ impl AudioSystem {
             self.buffer.clear();
         }
     }
-}
\ No newline at end of file
+}
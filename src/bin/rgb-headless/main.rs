@@ -0,0 +1,217 @@
+extern crate rgb;
+
+mod png;
+mod script;
+
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::process;
+
+use rgb::{Cartridge, CartridgeMapper, Gameboy, Model, RtcMode, SAVE_INTERVAL_FRAMES};
+use rgb::gb::image_source;
+
+use script::Script;
+
+pub const DEFAULT_FRAMES: usize = 600;
+
+struct Options {
+    cartridge_filepath: String,
+    bootrom_filepath: Option<String>,
+    model: Model,
+    mapper: Option<CartridgeMapper>,
+    frames: usize,
+    until_serial: Option<String>,
+    input_filepath: Option<String>,
+    screenshot_filepath: Option<String>,
+    serial_filepath: Option<String>,
+    save_directory: Option<String>,
+    no_save: bool,
+    rtc_mode: RtcMode,
+    camera_source: Option<String>,
+}
+
+fn usage() -> ! {
+    eprintln!("usage: rgb-headless <rom> [--bootrom FILE] [--model dmg|mgb] [--mapper NAME] [--frames N] [--until-serial TEXT] [--input FILE] [--screenshot FILE] [--serial FILE] [--save-dir DIR] [--no-save] [--rtc host|emulated] [--camera SOURCE]");
+    process::exit(2);
+}
+
+fn parse_args() -> Options {
+    let mut args = env::args().skip(1);
+
+    let mut cartridge_filepath = None;
+    let mut bootrom_filepath = None;
+    let mut model = Model::DMG;
+    let mut mapper = None;
+    let mut frames = DEFAULT_FRAMES;
+    let mut until_serial = None;
+    let mut input_filepath = None;
+    let mut screenshot_filepath = None;
+    let mut serial_filepath = None;
+    let mut save_directory = None;
+    let mut no_save = false;
+    let mut rtc_mode = RtcMode::EMULATED;
+    let mut camera_source = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bootrom" => bootrom_filepath = Some(args.next().unwrap_or_else(|| usage())),
+            "--model" => {
+                model = args.next().and_then(|value| Model::from_name(&value)).unwrap_or_else(|| usage());
+            },
+            "--mapper" => {
+                mapper = Some(args.next().and_then(|value| CartridgeMapper::from_name(&value)).unwrap_or_else(|| usage()));
+            },
+            "--frames" => {
+                frames = args.next().and_then(|value| value.parse().ok()).unwrap_or_else(|| usage());
+            },
+            "--until-serial" => until_serial = Some(args.next().unwrap_or_else(|| usage())),
+            "--input" => input_filepath = Some(args.next().unwrap_or_else(|| usage())),
+            "--screenshot" => screenshot_filepath = Some(args.next().unwrap_or_else(|| usage())),
+            "--serial" => serial_filepath = Some(args.next().unwrap_or_else(|| usage())),
+            "--save-dir" => save_directory = Some(args.next().unwrap_or_else(|| usage())),
+            "--no-save" => no_save = true,
+            "--camera" => camera_source = Some(args.next().unwrap_or_else(|| usage())),
+            "--rtc" => {
+                rtc_mode = args.next().and_then(|value| RtcMode::from_name(&value)).unwrap_or_else(|| usage());
+            },
+            _ if arg.starts_with("--") || cartridge_filepath.is_some() => usage(),
+            _ => cartridge_filepath = Some(arg),
+        }
+    }
+
+    Options {
+        cartridge_filepath: cartridge_filepath.unwrap_or_else(|| usage()),
+        bootrom_filepath,
+        model,
+        mapper,
+        frames,
+        until_serial,
+        input_filepath,
+        screenshot_filepath,
+        serial_filepath,
+        save_directory,
+        no_save,
+        rtc_mode,
+        camera_source,
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    needle.is_empty() || haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+fn flush_save(gb: &mut Gameboy) {
+    if let Err(e) = gb.flush_save() {
+        eprintln!("ERROR: failed to write save: {}", e);
+    }
+}
+
+fn main() {
+    let options = parse_args();
+
+    let mut script = match options.input_filepath {
+        Some(ref filepath) => Script::load(filepath).unwrap_or_else(|e| {
+            eprintln!("ERROR: {}", e);
+            process::exit(2);
+        }),
+        None => Script::new(),
+    };
+
+    let mut gb = Cartridge::new(&options.cartridge_filepath)
+        .and_then(|mut cartridge| {
+            if let Some(mapper) = options.mapper {
+                cartridge.set_mapper(mapper);
+            }
+
+            Gameboy::from_cartridge(cartridge)
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("ERROR: {}: {}", options.cartridge_filepath, e);
+            process::exit(2);
+        });
+
+    gb.set_model(options.model);
+
+    if let Some(ref filepath) = options.bootrom_filepath {
+        let result = fs::read(filepath).map_err(|e| e.to_string())
+            .and_then(|bootrom| gb.set_bootrom(Some(bootrom)).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            eprintln!("ERROR: {}: {}", filepath, e);
+            process::exit(2);
+        }
+    }
+
+    gb.set_rtc_mode(options.rtc_mode);
+
+    if let Some(ref source) = options.camera_source {
+        match image_source::open(source) {
+            Ok(image_source) => gb.set_image_source(image_source),
+            Err(e) => {
+                eprintln!("ERROR: {}: {}", source, e);
+                process::exit(2);
+            },
+        }
+    }
+
+    let save_filepath = if options.no_save {
+        None
+    } else {
+        Some(rgb::save_filepath(Path::new(&options.cartridge_filepath), options.save_directory.as_ref().map(Path::new)))
+    };
+
+    if let Some(ref filepath) = save_filepath {
+        if let Err(e) = gb.load_save(filepath) {
+            eprintln!("ERROR: {}: {}", filepath.display(), e);
+            process::exit(2);
+        }
+    }
+
+    gb.reset();
+
+    let mut frame = 0;
+    let mut condition_met = false;
+
+    while frame < options.frames {
+        script.apply(frame, &mut gb);
+        gb.run_frame();
+
+        frame += 1;
+
+        if frame % SAVE_INTERVAL_FRAMES == 0 {
+            flush_save(&mut gb);
+        }
+
+        if let Some(ref text) = options.until_serial {
+            if contains(gb.serial_output(), text.as_bytes()) {
+                condition_met = true;
+                break;
+            }
+        }
+    }
+
+    flush_save(&mut gb);
+
+    if let Some(ref filepath) = options.screenshot_filepath {
+        if let Err(e) = png::write(filepath, gb.framebuffer()) {
+            eprintln!("ERROR: {}: {}", filepath, e);
+            process::exit(2);
+        }
+    }
+
+    if let Some(ref filepath) = options.serial_filepath {
+        if let Err(e) = File::create(filepath).and_then(|mut f| f.write_all(gb.serial_output())) {
+            eprintln!("ERROR: {}: {}", filepath, e);
+            process::exit(2);
+        }
+    }
+
+    println!("Ran {} frames", frame);
+
+    if options.until_serial.is_some() && !condition_met {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,91 @@
+use std::fs::File;
+use std::io;
+use std::io::Write;
+
+use rgb::{PpuShade, PPU_DISPLAY_WIDTH, PPU_DISPLAY_HEIGHT};
+
+pub fn write(filepath: &str, framebuffer: &[PpuShade]) -> io::Result<()> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&(PPU_DISPLAY_WIDTH as u32).to_be_bytes());
+    header.extend_from_slice(&(PPU_DISPLAY_HEIGHT as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+    let mut pixels = Vec::with_capacity((PPU_DISPLAY_WIDTH + 1) * PPU_DISPLAY_HEIGHT);
+
+    for row in framebuffer.chunks(PPU_DISPLAY_WIDTH) {
+        pixels.push(0);
+
+        for shade in row {
+            pixels.push(match *shade {
+                PpuShade::WHITE => 0xff,
+                PpuShade::LIGHT => 0xaa,
+                PpuShade::DARK  => 0x55,
+                PpuShade::BLACK => 0x00,
+            });
+        }
+    }
+
+    let mut f = File::create(filepath)?;
+
+    f.write_all(&[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a])?;
+    write_chunk(&mut f, b"IHDR", &header)?;
+    write_chunk(&mut f, b"IDAT", &zlib_stored(&pixels))?;
+    write_chunk(&mut f, b"IEND", &[])
+}
+
+fn write_chunk(f: &mut File, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let mut crc_data = Vec::with_capacity(data.len() + 4);
+    crc_data.extend_from_slice(kind);
+    crc_data.extend_from_slice(data);
+
+    f.write_all(&(data.len() as u32).to_be_bytes())?;
+    f.write_all(&crc_data)?;
+    f.write_all(&crc32(&crc_data).to_be_bytes())
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+
+        stream.push(blocks.peek().is_none() as u8);
+        stream.extend_from_slice(&len.to_le_bytes());
+        stream.extend_from_slice(&(!len).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffffffffu32;
+
+    for byte in data {
+        crc ^= *byte as u32;
+
+        for _ in 0..8 {
+            crc = if (crc & 1) != 0 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let mut a = 1u32;
+    let mut b = 0u32;
+
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
@@ -0,0 +1,91 @@
+use std::fs::File;
+use std::io::Read;
+
+use rgb::{Button, Gameboy};
+
+struct ScriptEvent {
+    frame: usize,
+    button: Button,
+    pressed: bool,
+}
+
+pub struct Script {
+    events: Vec<ScriptEvent>,
+    position: usize,
+}
+
+impl Script {
+    pub fn new() -> Script {
+        Script {
+            events: Vec::new(),
+            position: 0,
+        }
+    }
+
+    pub fn load(filepath: &str) -> Result<Script, String> {
+        let mut f = File::open(filepath).map_err(|e| format!("{}: {}", filepath, e))?;
+        let mut source = String::new();
+
+        f.read_to_string(&mut source).map_err(|e| format!("{}: {}", filepath, e))?;
+
+        let mut script = Script::new();
+
+        for (number, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let event = parse_event(line).ok_or_else(|| format!("{}:{}: invalid input event", filepath, number + 1))?;
+            script.events.push(event);
+        }
+
+        script.events.sort_by_key(|event| event.frame);
+
+        Ok(script)
+    }
+
+    pub fn apply(&mut self, frame: usize, gb: &mut Gameboy) {
+        while self.position < self.events.len() && self.events[self.position].frame <= frame {
+            let event = &self.events[self.position];
+            gb.set_button(event.button, event.pressed);
+
+            self.position += 1;
+        }
+    }
+}
+
+fn parse_event(line: &str) -> Option<ScriptEvent> {
+    let mut fields = line.split_whitespace();
+
+    let frame = fields.next()?.parse().ok()?;
+
+    let button = match fields.next()?.to_lowercase().as_str() {
+        "a" => Button::A,
+        "b" => Button::B,
+        "start" => Button::START,
+        "select" => Button::SELECT,
+        "up" => Button::UP,
+        "down" => Button::DOWN,
+        "left" => Button::LEFT,
+        "right" => Button::RIGHT,
+        _ => return None,
+    };
+
+    let pressed = match fields.next()?.to_lowercase().as_str() {
+        "press" | "down" => true,
+        "release" | "up" => false,
+        _ => return None,
+    };
+
+    if fields.next().is_some() {
+        return None;
+    }
+
+    Some(ScriptEvent {
+        frame,
+        button,
+        pressed,
+    })
+}
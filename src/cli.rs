@@ -0,0 +1,135 @@
+use rgb::{CartridgeMapper, Model, RtcMode};
+
+use video_system::Palette;
+
+pub const USAGE: &str = "usage: rgb [options] <rom>
+       rgb --info <rom>
+
+options:
+    --bootrom <file>    run the given DMG boot ROM before the cartridge
+    --skip-boot         start the cartridge directly without a boot ROM (default)
+    --model <name>      hardware model: dmg or mgb (default dmg)
+    --mapper <name>     use the given mapper instead of the one in the header
+    --scale <n>         scale the window by n (default 3)
+    --palette <name>    colour palette: grey, green or pocket (default grey)
+    --mute              do not open an audio device
+    --fullscreen        start in fullscreen
+    --frames <n>        stop after n frames
+    --save-dir <dir>    keep battery saves in dir instead of next to the ROM
+    --no-save           do not read or write battery saves
+    --rtc <mode>        cartridge clock source: host or emulated (default host)
+    --camera <source>   Pocket Camera image: pattern, an image file or a directory of images
+    --headless          run without a window, audio or input
+    --info              print the cartridge header and exit
+    -h, --help          print this message";
+
+pub const DEFAULT_SCALE: u32 = 3;
+
+pub enum Command {
+    Run(Options),
+    Info(String),
+    Help,
+}
+
+pub struct Options {
+    pub cartridge_filepath: String,
+    pub bootrom_filepath: Option<String>,
+    pub skip_boot: bool,
+    pub model: Model,
+    pub mapper: Option<CartridgeMapper>,
+    pub scale: u32,
+    pub palette: Palette,
+    pub mute: bool,
+    pub fullscreen: bool,
+    pub frame_limit: Option<usize>,
+    pub save_directory: Option<String>,
+    pub no_save: bool,
+    pub rtc_mode: RtcMode,
+    pub camera_source: Option<String>,
+    pub headless: bool,
+}
+
+pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut cartridge_filepath = None;
+    let mut info = false;
+
+    let mut options = Options {
+        cartridge_filepath: String::new(),
+        bootrom_filepath: None,
+        skip_boot: false,
+        model: Model::DMG,
+        mapper: None,
+        scale: DEFAULT_SCALE,
+        palette: Palette::GREY,
+        mute: false,
+        fullscreen: false,
+        frame_limit: None,
+        save_directory: None,
+        no_save: false,
+        rtc_mode: RtcMode::HOST,
+        camera_source: None,
+        headless: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--info" => info = true,
+            "--bootrom" => options.bootrom_filepath = Some(value(&mut args, &arg)?),
+            "--skip-boot" => options.skip_boot = true,
+            "--model" => {
+                let name = value(&mut args, &arg)?;
+                options.model = Model::from_name(&name).ok_or(format!("unknown model '{}'", name))?;
+            },
+            "--mapper" => {
+                let name = value(&mut args, &arg)?;
+                options.mapper = Some(CartridgeMapper::from_name(&name).ok_or(format!("unknown mapper '{}'", name))?);
+            },
+            "--scale" => {
+                options.scale = match value(&mut args, &arg)?.parse() {
+                    Ok(scale) if scale > 0 => scale,
+                    _ => return Err("--scale expects a positive integer".to_string()),
+                };
+            },
+            "--palette" => {
+                let name = value(&mut args, &arg)?;
+                options.palette = Palette::from_name(&name).ok_or(format!("unknown palette '{}'", name))?;
+            },
+            "--mute" => options.mute = true,
+            "--fullscreen" => options.fullscreen = true,
+            "--frames" => {
+                let frames = value(&mut args, &arg)?;
+                options.frame_limit = Some(frames.parse().map_err(|_| "--frames expects an integer".to_string())?);
+            },
+            "--save-dir" => options.save_directory = Some(value(&mut args, &arg)?),
+            "--no-save" => options.no_save = true,
+            "--rtc" => {
+                let name = value(&mut args, &arg)?;
+                options.rtc_mode = RtcMode::from_name(&name).ok_or(format!("unknown clock source '{}'", name))?;
+            },
+            "--camera" => options.camera_source = Some(value(&mut args, &arg)?),
+            "--headless" => options.headless = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ if cartridge_filepath.is_some() => return Err(format!("unexpected argument '{}'", arg)),
+            _ => cartridge_filepath = Some(arg),
+        }
+    }
+
+    let cartridge_filepath = cartridge_filepath.ok_or("no ROM given")?;
+
+    if info {
+        return Ok(Command::Info(cartridge_filepath));
+    }
+
+    if options.skip_boot && options.bootrom_filepath.is_some() {
+        return Err("--bootrom and --skip-boot cannot be used together".to_string());
+    }
+
+    options.cartridge_filepath = cartridge_filepath;
+
+    Ok(Command::Run(options))
+}
+
+fn value<I: Iterator<Item = String>>(args: &mut I, option: &str) -> Result<String, String> {
+    args.next().ok_or(format!("{} expects a value", option))
+}
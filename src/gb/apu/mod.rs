@@ -2,14 +2,15 @@ mod noise;
 mod square;
 mod wave;
 
-use super::audio_system::AudioSystem;
+use super::audio_sink::{AudioSink, NullAudioSink};
 
 use self::noise::ApuNoise;
 use self::square::ApuSquare;
 use self::wave::ApuWave;
 
 pub struct Apu {
-    audio_system: AudioSystem,
+    audio_sink: Box<dyn AudioSink>,
+    samples: Vec<i16>,
 
     enable: bool,
 
@@ -33,10 +34,17 @@ pub struct Apu {
     wave: ApuWave,
 }
 
+impl Default for Apu {
+    fn default() -> Apu {
+        Apu::new()
+    }
+}
+
 impl Apu {
-    pub fn new(audio_system: AudioSystem) -> Apu {
+    pub fn new() -> Apu {
         Apu {
-            audio_system: audio_system,
+            audio_sink: Box::new(NullAudioSink),
+            samples: Vec::new(),
 
             enable: false,
 
@@ -115,13 +123,29 @@ impl Apu {
             if self.rs_period == 0 {
                 self.rs_period = 95;
 
-                self.audio_system.add_samples(&[l_out, r_out]);
+                self.samples.push(l_out);
+                self.samples.push(r_out);
             } else {
                 self.rs_period -= 1;
             }
         }
     }
 
+    pub fn set_audio_sink(&mut self, audio_sink: Box<dyn AudioSink>) {
+        self.audio_sink = audio_sink;
+    }
+
+    pub fn samples(&self) -> &[i16] {
+        &self.samples
+    }
+
+    pub fn flush_samples(&mut self) {
+        if !self.samples.is_empty() {
+            self.audio_sink.add_samples(&self.samples);
+            self.samples.clear();
+        }
+    }
+
     fn tick_frame_sequencer(&mut self) {
         if self.fs_period == 0 {
             self.fs_period = 8192;
@@ -214,9 +238,7 @@ impl Apu {
         0xff
     } 
 
-    pub fn nr20_write(&mut self, _: u8) {
-        return;
-    }
+    pub fn nr20_write(&mut self, _: u8) {}
 
     pub fn nr21_read(&mut self) -> u8 {
         self.square2.read_1()
@@ -326,9 +348,7 @@ impl Apu {
         0xff
     } 
 
-    pub fn nr40_write(&mut self, _: u8) {
-        return;
-    }
+    pub fn nr40_write(&mut self, _: u8) {}
 
     pub fn nr41_read(&mut self) -> u8 {
         self.noise.read_1()
@@ -443,9 +463,9 @@ impl Apu {
         self.enable = (value & 0x80) != 0;
 
         if self.enable {
-            self.audio_system.resume();
+            self.audio_sink.resume();
         } else {
-            self.audio_system.pause();
+            self.audio_sink.pause();
         }
 
         if !self.enable {
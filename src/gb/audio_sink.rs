@@ -0,0 +1,12 @@
+pub trait AudioSink {
+    fn add_samples(&mut self, samples: &[i16]);
+
+    fn pause(&mut self) {}
+    fn resume(&mut self) {}
+}
+
+pub struct NullAudioSink;
+
+impl AudioSink for NullAudioSink {
+    fn add_samples(&mut self, _: &[i16]) {}
+}
@@ -1,11 +1,25 @@
 use super::apu::Apu;
-use super::audio_system::AudioSystem;
 use super::mapper::Mapper;
+use super::model::Model;
 use super::ppu::Ppu;
 use super::timer::Timer;
-use super::video_system::VideoSystem;
 
-const BOOTROM: &'static [u8] = include_bytes!("../../bootrom/DMG_ROM.bin");
+const POST_BOOT_DIVIDER: u16 = 0xabcc;
+
+// NRx4 values are written without the trigger bit so no channel restarts.
+const POST_BOOT_IO: [(u16, u8); 34] = [
+    (0xff00, 0xcf), (0xff02, 0x7e), (0xff05, 0x00), (0xff06, 0x00),
+    (0xff07, 0xf8), (0xff26, 0xf1), (0xff10, 0x80), (0xff11, 0xbf),
+    (0xff12, 0xf3), (0xff13, 0xff), (0xff14, 0x3f), (0xff16, 0x3f),
+    (0xff17, 0x00), (0xff18, 0xff), (0xff19, 0x3f), (0xff1a, 0x7f),
+    (0xff1b, 0xff), (0xff1c, 0x9f), (0xff1d, 0xff), (0xff1e, 0x3f),
+    (0xff20, 0xff), (0xff21, 0x00), (0xff22, 0x00), (0xff23, 0x3f),
+    (0xff24, 0x77), (0xff25, 0xf3), (0xff40, 0x91), (0xff42, 0x00),
+    (0xff43, 0x00), (0xff45, 0x00), (0xff47, 0xfc), (0xff4a, 0x00),
+    (0xff4b, 0x00), (0xff0f, 0xe1),
+];
+
+const LOGO_REGISTERED: [u8; 8] = [0x3c, 0x42, 0xb9, 0xa5, 0xb9, 0xa5, 0x42, 0x3c];
 
 bitflags! {
     pub struct Interrupts: u8 {
@@ -20,10 +34,12 @@ bitflags! {
 pub struct Bus {
     latch: u8,
 
+    model: Model,
+
     bootrom: Box<[u8]>,
     bootrom_enabled: bool,
 
-    mapper: Box<Mapper + Send>,
+    mapper: Box<dyn Mapper + Send>,
 
     apu: Apu,
     ppu: Ppu,
@@ -32,6 +48,7 @@ pub struct Bus {
 
     serial_buffer: u8,
     serial_control: u8,
+    serial_output: Vec<u8>,
 
     timer: Timer,
 
@@ -39,25 +56,30 @@ pub struct Bus {
 
     interrupt_enable: Interrupts,
     interrupt_flag: Interrupts,
+
+    cycles: u64,
 }
 
 impl Bus {
-    pub fn new(mapper: Box<Mapper + Send>, audio_system: AudioSystem, video_system: VideoSystem) -> Bus {
+    pub fn new(mapper: Box<dyn Mapper + Send>) -> Bus {
         Bus {
             latch: 0,
 
-            bootrom: Box::from(BOOTROM),
-            bootrom_enabled: true,
+            model: Model::DMG,
 
-            mapper: mapper,
+            bootrom: Box::new([]),
+            bootrom_enabled: false,
 
-            apu: Apu::new(audio_system),
-            ppu: Ppu::new(video_system),
+            mapper,
+
+            apu: Apu::new(),
+            ppu: Ppu::new(),
 
             work_ram: vec![0; 0x2000].into_boxed_slice(),
 
             serial_buffer: 0,
             serial_control: 0,
+            serial_output: Vec::new(),
 
             timer: Timer::new(),
 
@@ -65,6 +87,8 @@ impl Bus {
 
             interrupt_enable: Interrupts::empty(),
             interrupt_flag: Interrupts::empty(),
+
+            cycles: 0,
         }
     }
 
@@ -73,44 +97,40 @@ impl Bus {
             self.latch = self.bootrom[address as usize];
         }
         
-        else if address < 0x0100 {
-            self.latch = self.mapper.read_rom(address);
-        }
-        
-        else if address >= 0x0100 && address < 0x8000 {
+        else if address < 0x8000 {
             self.latch = self.mapper.read_rom(address);
         }
         
-        else if address >= 0x8000 && address < 0xa000 {
-            self.latch = self.ppu.vram_read(address as u16);
+        else if (0x8000..0xa000).contains(&address) {
+            self.latch = self.ppu.vram_read(address);
         }
         
-        else if address >= 0xa000 && address < 0xc000 {
+        else if (0xa000..0xc000).contains(&address) {
             self.latch = self.mapper.read_ram(address);
         }
 
-        else if address >= 0xc000 && address < 0xe000 {
+        else if (0xc000..0xe000).contains(&address) {
             self.latch = self.work_ram[address as usize - 0xc000];
         }
 
-        else if address >= 0xe000 && address < 0xfe00 {
+        else if (0xe000..0xfe00).contains(&address) {
             self.latch = self.work_ram[address as usize - 0xe000];
         }
 
-        else if address >= 0xfe00 && address < 0xfea0 {
+        else if (0xfe00..0xfea0).contains(&address) {
             self.latch = self.ppu.vram_read(address);
         }
 
-        else if address >= 0xfea0 && address < 0xff00 {
+        else if (0xfea0..0xff00).contains(&address) {
             println!("WARN: read from unusable memory");
             self.latch = 0x00;
         }
 
-        else if address >= 0xff00 && address < 0xff80 {
+        else if (0xff00..0xff80).contains(&address) {
             self.latch = self.io_read(address);
         }
         
-        else if address >= 0xff80 && address < 0xffff {
+        else if (0xff80..0xffff).contains(&address) {
             self.latch = self.high_ram[address as usize - 0xff80];
         }
 
@@ -133,35 +153,35 @@ impl Bus {
             self.mapper.write_rom(address, latch);
         }
 
-        else if address >= 0x8000 && address < 0xa000 {
+        else if (0x8000..0xa000).contains(&address) {
             self.ppu.vram_write(address, latch);
         }
         
-        else if address >= 0xa000 && address < 0xc000 {
+        else if (0xa000..0xc000).contains(&address) {
             self.mapper.write_ram(address, latch);
         }
 
-        else if address >= 0xc000 && address < 0xe000 {
+        else if (0xc000..0xe000).contains(&address) {
             self.work_ram[address as usize - 0xc000] = latch;
         }
 
-        else if address >= 0xe000 && address < 0xfe00 {
+        else if (0xe000..0xfe00).contains(&address) {
             self.work_ram[address as usize - 0xe000] = latch;
         }
 
-        else if address >= 0xfe00 && address < 0xfea0 {
+        else if (0xfe00..0xfea0).contains(&address) {
             self.ppu.vram_write(address, latch)
         }
 
-        else if address >= 0xfea0 && address < 0xff00 {
+        else if (0xfea0..0xff00).contains(&address) {
 
         }
 
-        else if address >= 0xff00 && address < 0xff80 {
+        else if (0xff00..0xff80).contains(&address) {
             self.io_write(address, latch);
         }
         
-        else if address >= 0xff80 && address < 0xffff {
+        else if (0xff80..0xffff).contains(&address) {
             self.high_ram[address as usize - 0xff80] = latch;
         }
 
@@ -207,8 +227,8 @@ impl Bus {
             0xff24 => self.apu.nr50_read(),
             0xff25 => self.apu.nr51_read(),
             0xff26 => self.apu.nr52_read(),
-            0xff27...0xff2f => 0xff,
-            0xff30...0xff3f => self.apu.read_wavetable(address),
+            0xff27..=0xff2f => 0xff,
+            0xff30..=0xff3f => self.apu.read_wavetable(address),
             0xff40 => self.ppu.lcdc_read(),
             0xff41 => self.ppu.stat_read(),
             0xff42 => self.ppu.scy_read(),
@@ -218,6 +238,8 @@ impl Bus {
             0xff47 => self.ppu.bgp_read(),
             0xff48 => self.ppu.obp1_read(),
             0xff49 => self.ppu.obp2_read(),
+            0xff4a => self.ppu.wy_read(),
+            0xff4b => self.ppu.wx_read(),
             _ => { println!("ERROR: read from unimplemented i/o register 0x{:04x}", address); 0xff },
         };
 
@@ -233,6 +255,7 @@ impl Bus {
 
                 if self.serial_control == 0x81 {
                     println!("SERIAL TRANSFER: {}", self.serial_buffer as char);
+                    self.serial_output.push(self.serial_buffer);
                 }
             },
             0xff04 => self.timer.div_write(value),
@@ -263,8 +286,8 @@ impl Bus {
             0xff24 => self.apu.nr50_write(value),
             0xff25 => self.apu.nr51_write(value),
             0xff26 => self.apu.nr52_write(value),
-            0xff27...0xff2f => (),
-            0xff30...0xff3f => self.apu.write_wavetable(address, value),
+            0xff27..=0xff2f => (),
+            0xff30..=0xff3f => self.apu.write_wavetable(address, value),
             0xff40 => self.ppu.lcdc_write(value),
             0xff41 => self.ppu.stat_write(value),
             0xff42 => self.ppu.scy_write(value),
@@ -282,6 +305,8 @@ impl Bus {
             0xff47 => self.ppu.bgp_write(value),
             0xff48 => self.ppu.obp1_write(value),
             0xff49 => self.ppu.obp2_write(value),
+            0xff4a => self.ppu.wy_write(value),
+            0xff4b => self.ppu.wx_write(value),
             0xff50 => self.bootrom_enabled = false,
             0xff7f => (),
             _ => println!("WARN: write to unimplemented i/o register 0x{:04x}", address),
@@ -308,9 +333,109 @@ impl Bus {
         self.interrupt_flag.bits()
     }
 
+    pub fn mapper(&self) -> &(dyn Mapper + Send) {
+        &*self.mapper
+    }
+
+    pub fn mapper_mut(&mut self) -> &mut (dyn Mapper + Send) {
+        &mut *self.mapper
+    }
+
+    pub fn apu(&self) -> &Apu {
+        &self.apu
+    }
+
+    pub fn apu_mut(&mut self) -> &mut Apu {
+        &mut self.apu
+    }
+
+    pub fn ppu(&self) -> &Ppu {
+        &self.ppu
+    }
+
+    pub fn ppu_mut(&mut self) -> &mut Ppu {
+        &mut self.ppu
+    }
+
+    pub fn get_model(&self) -> Model {
+        self.model
+    }
+
+    pub fn set_model(&mut self, model: Model) {
+        self.model = model;
+    }
+
+    pub fn skip_bootrom(&mut self) {
+        for &(address, value) in POST_BOOT_IO.iter() {
+            self.write(address, value);
+        }
+
+        self.timer.set_divider(POST_BOOT_DIVIDER);
+
+        self.write_logo();
+        self.mapper.skip_bootrom();
+
+        self.bootrom_enabled = false;
+    }
+
+    fn write_logo(&mut self) {
+        let mut address = 0x8010;
+
+        for i in 0..0x30 {
+            let logo = self.mapper.read_rom(0x104 + i);
+
+            for &nibble in [logo >> 4, logo & 0x0f].iter() {
+                let mut row = 0;
+
+                for bit in 0..4 {
+                    if (nibble & (0x08 >> bit)) != 0 {
+                        row |= 0xc0 >> (bit * 2);
+                    }
+                }
+
+                for _ in 0..2 {
+                    self.ppu.vram_write(address, row);
+                    address += 2;
+                }
+            }
+        }
+
+        for &row in LOGO_REGISTERED.iter() {
+            self.ppu.vram_write(address, row);
+            address += 2;
+        }
+
+        self.ppu.vram_write(0x9910, 0x19);
+
+        for i in 0..12 {
+            self.ppu.vram_write(0x9924 + i, 0x0d + i as u8);
+            self.ppu.vram_write(0x9904 + i, 0x01 + i as u8);
+        }
+    }
+
+    pub fn set_bootrom(&mut self, bootrom: Box<[u8]>) {
+        self.bootrom_enabled = !bootrom.is_empty();
+        self.bootrom = bootrom;
+    }
+
+    pub fn has_bootrom(&self) -> bool {
+        !self.bootrom.is_empty()
+    }
+
+    pub fn serial_output(&self) -> &[u8] {
+        &self.serial_output
+    }
+
+    pub fn get_cycles(&self) -> u64 {
+        self.cycles
+    }
+
     pub fn tick(&mut self) {
+        self.cycles += 4;
+
         self.apu.tick(4);
         self.ppu.tick(4);
         self.timer.tick(4);
+        self.mapper.tick(4);
     }
 }
\ No newline at end of file
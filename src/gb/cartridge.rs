@@ -1,23 +1,95 @@
 use std::fs::File;
 use std::io::Read;
 
+use super::error::LoadError;
+use super::gbx::{self, GbxFooter};
+
+pub const CARTRIDGE_HEADER_END: usize = 0x150;
+pub const MBC2_RAM_SIZE: usize = 0x200;
+pub const TAMA5_RAM_SIZE: usize = 0x20;
+pub const MBC7_EEPROM_SIZE: usize = 0x100;
+pub const MBC1M_ROM_SIZE: usize = 0x100000;
+pub const MMM01_MENU_SIZE: usize = 0x8000;
+pub const M161_GAME_SIZE: usize = 0x8000;
+
+pub const NINTENDO_LOGO: [u8; 0x30] = [
+    0xce, 0xed, 0x66, 0x66, 0xcc, 0x0d, 0x00, 0x0b, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0c, 0x00, 0x0d,
+    0x00, 0x08, 0x11, 0x1f, 0x88, 0x89, 0x00, 0x0e, 0xdc, 0xcc, 0x6e, 0xe6, 0xdd, 0xdd, 0xd9, 0x99,
+    0xbb, 0xbb, 0x67, 0x63, 0x6e, 0x0e, 0xec, 0xcc, 0xdd, 0xdc, 0x99, 0x9f, 0xbb, 0xb9, 0x33, 0x3e,
+];
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CartridgeMapper {
     NONE,
     MBC1,
+    MBC1M,
     MBC2,
     MMM01,
     MBC3,
     MBC4,
     MBC5,
+    MBC6,
+    MBC7,
     POCKETCAMERA,
     TAMA5,
     HUC3,
     HUC1,
+    WISDOMTREE,
+    SACHENMMC1,
+    SACHENMMC2,
+    M161,
     INVALID
 }
 
-#[derive(Debug)]
+impl CartridgeMapper {
+    pub fn from_name(name: &str) -> Option<CartridgeMapper> {
+        match name {
+            "none" => Some(CartridgeMapper::NONE),
+            "mbc1" => Some(CartridgeMapper::MBC1),
+            "mbc1m" => Some(CartridgeMapper::MBC1M),
+            "mbc2" => Some(CartridgeMapper::MBC2),
+            "mmm01" => Some(CartridgeMapper::MMM01),
+            "mbc3" => Some(CartridgeMapper::MBC3),
+            "mbc5" => Some(CartridgeMapper::MBC5),
+            "mbc6" => Some(CartridgeMapper::MBC6),
+            "mbc7" => Some(CartridgeMapper::MBC7),
+            "camera" => Some(CartridgeMapper::POCKETCAMERA),
+            "tama5" => Some(CartridgeMapper::TAMA5),
+            "huc3" => Some(CartridgeMapper::HUC3),
+            "huc1" => Some(CartridgeMapper::HUC1),
+            "wisdomtree" => Some(CartridgeMapper::WISDOMTREE),
+            "sachen-mmc1" => Some(CartridgeMapper::SACHENMMC1),
+            "sachen-mmc2" => Some(CartridgeMapper::SACHENMMC2),
+            "m161" => Some(CartridgeMapper::M161),
+            _ => None,
+        }
+    }
+
+    pub fn from_gbx_id(id: &[u8; 4]) -> Option<CartridgeMapper> {
+        match id {
+            b"ROM\0" | b"ROM " => Some(CartridgeMapper::NONE),
+            b"MBC1" => Some(CartridgeMapper::MBC1),
+            b"MB1M" => Some(CartridgeMapper::MBC1M),
+            b"MBC2" => Some(CartridgeMapper::MBC2),
+            b"MMM1" => Some(CartridgeMapper::MMM01),
+            b"MBC3" => Some(CartridgeMapper::MBC3),
+            b"MBC5" => Some(CartridgeMapper::MBC5),
+            b"MBC6" => Some(CartridgeMapper::MBC6),
+            b"MBC7" => Some(CartridgeMapper::MBC7),
+            b"CAMR" => Some(CartridgeMapper::POCKETCAMERA),
+            b"TAM5" => Some(CartridgeMapper::TAMA5),
+            b"HUC3" => Some(CartridgeMapper::HUC3),
+            b"HUC1" => Some(CartridgeMapper::HUC1),
+            b"WISD" => Some(CartridgeMapper::WISDOMTREE),
+            b"SAM1" => Some(CartridgeMapper::SACHENMMC1),
+            b"SAM2" => Some(CartridgeMapper::SACHENMMC2),
+            b"M161" => Some(CartridgeMapper::M161),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
 pub struct CartridgeType {
     mapper: CartridgeMapper,
     ram: bool,
@@ -40,6 +112,35 @@ impl CartridgeType {
     pub fn get_mapper(&self) -> CartridgeMapper {
         self.mapper
     }
+
+    pub fn has_ram(&self) -> bool {
+        self.ram
+    }
+
+    pub fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    pub fn has_timer(&self) -> bool {
+        self.timer
+    }
+
+    pub fn has_rumble(&self) -> bool {
+        self.rumble
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeaderValidation {
+    pub logo: bool,
+    pub header_checksum: bool,
+    pub global_checksum: bool,
+}
+
+impl HeaderValidation {
+    pub fn is_valid(&self) -> bool {
+        self.logo && self.header_checksum && self.global_checksum
+    }
 }
 
 #[derive(Debug)]
@@ -52,41 +153,192 @@ pub enum CartridgeLanguage {
 pub struct Cartridge {
     rom: Box<[u8]>,
     ram: Box<[u8]>,
+    ram_dirty: bool,
+    cartridge_type: CartridgeType,
+    header_offset: usize,
+    mapped_rom_size: usize,
+    gbx: Option<GbxFooter>,
 }
 
 impl Cartridge {
-    pub fn new(filepath: &str) -> Cartridge {
-        let mut f = File::open(filepath).unwrap();
+    pub fn new(filepath: &str) -> Result<Cartridge, LoadError> {
+        let mut f = File::open(filepath)?;
         let mut buffer = Vec::new();
 
-        let _ = f.read_to_end(&mut buffer);
+        f.read_to_end(&mut buffer)?;
 
-        let ram_size = match buffer[0x149] {
-            0x00 => 0x0,
-            0x01 => 0x800,
-            0x02 => 0x2000,
-            0x03 => 0x8000,
-            0x04 => 0x20000,
-            0x05 => 0x10000,
-            _    => panic!("ERROR: invalid cartridge ram size")
-        };
+        Cartridge::from_bytes(buffer)
+    }
 
-        Cartridge {
+    pub fn from_bytes(mut buffer: Vec<u8>) -> Result<Cartridge, LoadError> {
+        let gbx = gbx::split_footer(&mut buffer)?;
+
+        if buffer.len() < CARTRIDGE_HEADER_END {
+            return Err(LoadError::TRUNCATED(buffer.len()));
+        }
+
+        let header_offset = mmm01_header_offset(&mut buffer);
+
+        let mut cartridge = Cartridge {
             rom: buffer.into_boxed_slice(),
-            ram: vec![0u8; ram_size].into_boxed_slice(),
+            ram: Box::new([]),
+            ram_dirty: false,
+            cartridge_type: CartridgeType::new(CartridgeMapper::INVALID, false, false, false, false),
+            header_offset,
+            mapped_rom_size: 0,
+            gbx,
+        };
+
+        cartridge.cartridge_type = cartridge.decode_type();
+
+        if let Some(footer) = gbx {
+            let mapper = CartridgeMapper::from_gbx_id(&footer.mapper_id).ok_or_else(|| LoadError::UNSUPPORTEDGBXMAPPER(footer.mapper_name()))?;
+
+            cartridge.cartridge_type = CartridgeType::new(mapper, footer.ram_size > 0, footer.battery, footer.timer, footer.rumble);
+            cartridge.mapped_rom_size = footer.rom_size;
+        } else if let Some((mapper, reason)) = cartridge.detect_unlicensed() {
+            println!("WARN: running cartridge as {:?}: {}", mapper, reason);
+
+            let ram = [CartridgeMapper::MBC1, CartridgeMapper::MBC5].contains(&mapper) && cartridge.get_ram_size().unwrap_or(0) > 0;
+
+            cartridge.cartridge_type = CartridgeType::new(mapper, ram, ram, false, false);
+            cartridge.mapped_rom_size = cartridge.rom.len().next_power_of_two();
+        } else {
+            let rom_size = cartridge.get_rom_size().map_err(|_| LoadError::INVALIDROMSIZE(cartridge.header(0x148)))?;
+            cartridge.get_ram_size().map_err(|_| LoadError::INVALIDRAMSIZE(cartridge.header(0x149)))?;
+
+            if cartridge.rom.len() < rom_size {
+                return Err(LoadError::SIZEMISMATCH { expected: rom_size, actual: cartridge.rom.len() });
+            }
+
+            cartridge.mapped_rom_size = rom_size;
+
+            if cartridge.cartridge_type.get_mapper() == CartridgeMapper::MBC1 && cartridge.is_mbc1_multicart() {
+                cartridge.cartridge_type.mapper = CartridgeMapper::MBC1M;
+            }
         }
+
+        if cartridge.cartridge_type.get_mapper() == CartridgeMapper::MMM01 && cartridge.mapped_rom_size < MMM01_MENU_SIZE * 2 {
+            return Err(LoadError::INVALIDMMM01SIZE(cartridge.mapped_rom_size));
+        }
+
+        cartridge.allocate_ram();
+
+        Ok(cartridge)
     }
 
-    pub fn read_rom(&self, address: usize) -> u8 {
-        if address < self.get_rom_size().unwrap() {
-            self.rom[address]
+    fn allocate_ram(&mut self) {
+        let ram_size = match self.cartridge_type.get_mapper() {
+            CartridgeMapper::MBC2 => MBC2_RAM_SIZE,
+            CartridgeMapper::TAMA5 => TAMA5_RAM_SIZE,
+            CartridgeMapper::MBC7 => MBC7_EEPROM_SIZE,
+            _ if self.cartridge_type.has_ram() => self.declared_ram_size(),
+            _ => 0,
+        };
+
+        let erased = if self.cartridge_type.get_mapper() == CartridgeMapper::MBC7 { 0xff } else { 0x00 };
+
+        self.ram = vec![erased; ram_size].into_boxed_slice();
+        self.ram_dirty = false;
+    }
+
+    fn declared_ram_size(&self) -> usize {
+        match self.gbx {
+            Some(footer) => footer.ram_size,
+            None => self.get_ram_size().unwrap_or(0),
+        }
+    }
+
+    fn header(&self, address: usize) -> u8 {
+        self.rom[self.header_offset + address]
+    }
+
+    fn is_mbc1_multicart(&self) -> bool {
+        self.rom.len() == MBC1M_ROM_SIZE && self.has_logo_at(0x104) && self.has_logo_at((0x10 * 0x4000) + 0x104)
+    }
+
+    fn has_logo_at(&self, address: usize) -> bool {
+        address + NINTENDO_LOGO.len() <= self.rom.len() && self.rom[address..address + NINTENDO_LOGO.len()] == NINTENDO_LOGO
+    }
+
+    fn detect_sachen(&self) -> Option<CartridgeMapper> {
+        if self.has_logo_at(0x104) {
+            return None;
+        }
+
+        let scrambled_logo = |offset: u16| {
+            (0..NINTENDO_LOGO.len()).all(|i| {
+                let address = unscramble_sachen((0x104 + i as u16) | offset) as usize;
+                self.rom.get(address) == Some(&NINTENDO_LOGO[i])
+            })
+        };
+
+        if scrambled_logo(0x00) {
+            Some(CartridgeMapper::SACHENMMC2)
+        } else if scrambled_logo(0x80) {
+            Some(CartridgeMapper::SACHENMMC1)
         } else {
-            panic!("ERROR: read from out of bounds rom address")
+            None
         }
     }
 
+    fn is_m161_multicart(&self) -> bool {
+        self.rom.len() == M161_GAME_SIZE * 8 && (1..8).all(|game| self.has_logo_at((game * M161_GAME_SIZE) + 0x104))
+    }
+
+    fn detect_unlicensed(&self) -> Option<(CartridgeMapper, &'static str)> {
+        if self.header_offset != 0 {
+            return None;
+        }
+
+        if let Some(mapper) = self.detect_sachen() {
+            return Some((mapper, "scrambled Sachen logo"));
+        }
+
+        let mapper = self.cartridge_type.get_mapper();
+
+        if mapper != CartridgeMapper::INVALID && (mapper != CartridgeMapper::NONE || self.rom.len() <= 0x8000) {
+            return None;
+        }
+
+        if contains(&self.rom, b"WISDOM TREE") || contains(&self.rom, b"WISDOM\x00TREE") {
+            Some((CartridgeMapper::WISDOMTREE, "Wisdom Tree name in ROM"))
+        } else if self.is_m161_multicart() {
+            Some((CartridgeMapper::M161, "Nintendo logo in every 32kb game"))
+        } else if self.rom.len() <= 0x8000 {
+            None
+        } else if contains(&self.rom, &[0xea, 0x00, 0x30]) {
+            Some((CartridgeMapper::MBC5, "MBC5 high bank writes in ROM"))
+        } else if contains(&self.rom, &[0xea, 0x00, 0x20]) || contains(&self.rom, &[0xea, 0x00, 0x21]) {
+            Some((CartridgeMapper::MBC1, "MBC1 bank writes in ROM"))
+        } else {
+            None
+        }
+    }
+
+    pub fn set_mapper(&mut self, mapper: CartridgeMapper) {
+        let internal_ram = [CartridgeMapper::MBC2, CartridgeMapper::TAMA5, CartridgeMapper::MBC7].contains(&mapper);
+        let ram = internal_ram || self.declared_ram_size() > 0;
+
+        self.cartridge_type = CartridgeType::new(mapper, ram, ram, self.cartridge_type.has_timer(), self.cartridge_type.has_rumble());
+        self.mapped_rom_size = self.mapped_rom_size.max(self.rom.len().next_power_of_two());
+        self.allocate_ram();
+    }
+
+    pub fn get_gbx_footer(&self) -> Option<&GbxFooter> {
+        self.gbx.as_ref()
+    }
+
+    pub fn get_mapped_rom_size(&self) -> usize {
+        self.mapped_rom_size
+    }
+
+    pub fn read_rom(&self, address: usize) -> u8 {
+        self.rom.get(address).cloned().unwrap_or(0xff)
+    }
+
     pub fn read_ram(&self, address: usize) -> u8 {
-        if address < self.get_ram_size().unwrap() {
+        if address < self.ram.len() {
             self.ram[address]
         } else {
             println!("WARN: read from out of bounds ram address");
@@ -99,24 +351,43 @@ impl Cartridge {
     }
 
     pub fn write_ram(&mut self, address: usize, value: u8) {
-        if address < self.get_ram_size().unwrap() {
+        if address < self.ram.len() {
+            self.ram_dirty |= self.ram[address] != value;
             self.ram[address] = value
         } else {
             println!("WARN: write to out of bounds ram address");
         }
     }
 
+    pub fn is_ram_dirty(&self) -> bool {
+        self.ram_dirty
+    }
+
+    pub fn load_ram(&mut self, data: &[u8]) {
+        if data.len() != self.ram.len() {
+            println!("WARN: save data is {} bytes, expected {}", data.len(), self.ram.len());
+        }
+
+        let length = data.len().min(self.ram.len());
+        self.ram[..length].copy_from_slice(&data[..length]);
+
+        self.ram_dirty = false;
+    }
+
+    pub fn save_ram(&mut self) -> Vec<u8> {
+        self.ram_dirty = false;
+        self.ram.to_vec()
+    }
+
     pub fn get_title(&self) -> String {
         let mut title = String::new();
-        let mut title_length = 0;
-
-        let mut character = self.rom[0x134] as char;
 
-        while character != '\0' {
-            title.push(character);
-            title_length += 1;
+        for &character in self.rom[self.header_offset + 0x134..self.header_offset + 0x144].iter() {
+            if character == 0 {
+                break;
+            }
 
-            character = self.rom[0x134 + title_length] as char;
+            title.push(character as char);
         }
 
         title
@@ -127,7 +398,7 @@ impl Cartridge {
     }
 
     pub fn get_rom_size(&self) -> Result<usize, &str> {
-        match self.rom[0x148] {
+        match self.header(0x148) {
             0x00 => Ok(0x8000),
             0x01 => Ok(0x10000),
             0x02 => Ok(0x20000),
@@ -142,7 +413,7 @@ impl Cartridge {
     }
 
     pub fn get_rom_banks(&self) -> Result<usize, &str> {
-        match self.rom[0x148] {
+        match self.header(0x148) {
             0x00 => Ok(2),
             0x01 => Ok(4),
             0x02 => Ok(8),
@@ -157,7 +428,19 @@ impl Cartridge {
     }
 
     pub fn get_ram_size(&self) -> Result<usize, &str> {
-        match self.rom[0x149] {
+        match self.header(0x149) {
+            0x00 => Ok(0),
+            0x01 => Ok(0x800),
+            0x02 => Ok(0x2000),
+            0x03 => Ok(0x8000),
+            0x04 => Ok(0x20000),
+            0x05 => Ok(0x10000),
+            _ => Err("ERROR: invalid cartridge RAM size")
+        }
+    }
+
+    pub fn get_ram_banks(&self) -> Result<usize, &str> {
+        match self.header(0x149) {
             0x00 => Ok(0),
             0x01 => Ok(1),
             0x02 => Ok(1),
@@ -168,35 +451,95 @@ impl Cartridge {
         }
     }
 
-    pub fn get_ram_banks(&self) -> Result<usize, &str> {
-        match self.rom[0x149] {
-            0x00 => Ok(0),
-            0x01 => Ok(0x800),
-            0x02 => Ok(0x2000),
-            0x03 => Ok(0x8000),
-            0x04 => Ok(0x20000),
-            0x05 => Ok(0x10000),
-            _ => Err("ERROR: invalid cartridge RAM size")
-        }
+    pub fn get_ram_length(&self) -> usize {
+        self.ram.len()
     }
 
     pub fn has_ram(&self) -> bool {
-        self.get_ram_size().unwrap() > 0
+        !self.ram.is_empty()
     }
 
     pub fn get_language(&self) -> CartridgeLanguage {
-        match self.rom[0x14a] {
+        match self.header(0x14a) {
             0x00 => CartridgeLanguage::JAPANESE,
             0x01 => CartridgeLanguage::ENGLISH,
             _  => CartridgeLanguage::INVALID
         }
     }
 
+    pub fn get_type_byte(&self) -> u8 {
+        self.header(0x147)
+    }
+
+    pub fn has_valid_logo(&self) -> bool {
+        self.has_logo_at(self.header_offset + 0x104)
+    }
+
+    pub fn get_header_checksum(&self) -> u8 {
+        self.header(0x14d)
+    }
+
+    pub fn compute_header_checksum(&self) -> u8 {
+        (0x134..0x14d).fold(0u8, |checksum, address| checksum.wrapping_sub(self.header(address)).wrapping_sub(1))
+    }
+
+    pub fn get_global_checksum(&self) -> u16 {
+        ((self.header(0x14e) as u16) << 8) | self.header(0x14f) as u16
+    }
+
+    pub fn compute_global_checksum(&self) -> u16 {
+        let checksum_address = self.header_offset + 0x14e;
+
+        self.rom.iter().enumerate()
+            .filter(|&(address, _)| address != checksum_address && address != checksum_address + 1)
+            .fold(0u16, |checksum, (_, &value)| checksum.wrapping_add(value as u16))
+    }
+
+    pub fn validate_header(&self) -> HeaderValidation {
+        HeaderValidation {
+            logo: self.has_valid_logo(),
+            header_checksum: self.get_header_checksum() == self.compute_header_checksum(),
+            global_checksum: self.get_global_checksum() == self.compute_global_checksum(),
+        }
+    }
+
     pub fn get_type(&self) -> CartridgeType {
-        match self.rom[0x147] {
+        self.cartridge_type
+    }
+
+    fn decode_type(&self) -> CartridgeType {
+        match self.header(0x147) {
             0x00 => CartridgeType::new(CartridgeMapper::NONE, false, false, false, false),
-            0x01...0x03 => CartridgeType::new(CartridgeMapper::MBC1, false, false, false, false),
-            0x13 => CartridgeType::new(CartridgeMapper::MBC3, false, false, false, false),
+            0x01 => CartridgeType::new(CartridgeMapper::MBC1, false, false, false, false),
+            0x02 => CartridgeType::new(CartridgeMapper::MBC1, true, false, false, false),
+            0x03 => CartridgeType::new(CartridgeMapper::MBC1, true, true, false, false),
+            0x05 => CartridgeType::new(CartridgeMapper::MBC2, true, false, false, false),
+            0x06 => CartridgeType::new(CartridgeMapper::MBC2, true, true, false, false),
+            0x08 => CartridgeType::new(CartridgeMapper::NONE, true, false, false, false),
+            0x09 => CartridgeType::new(CartridgeMapper::NONE, true, true, false, false),
+            0x0b => CartridgeType::new(CartridgeMapper::MMM01, false, false, false, false),
+            0x0c => CartridgeType::new(CartridgeMapper::MMM01, true, false, false, false),
+            0x0d => CartridgeType::new(CartridgeMapper::MMM01, true, true, false, false),
+            0x0f => CartridgeType::new(CartridgeMapper::MBC3, false, true, true, false),
+            0x10 => CartridgeType::new(CartridgeMapper::MBC3, true, true, true, false),
+            0x11 => CartridgeType::new(CartridgeMapper::MBC3, false, false, false, false),
+            0x12 => CartridgeType::new(CartridgeMapper::MBC3, true, false, false, false),
+            0x13 => CartridgeType::new(CartridgeMapper::MBC3, true, true, false, false),
+            0x15 => CartridgeType::new(CartridgeMapper::MBC4, false, false, false, false),
+            0x16 => CartridgeType::new(CartridgeMapper::MBC4, true, false, false, false),
+            0x17 => CartridgeType::new(CartridgeMapper::MBC4, true, true, false, false),
+            0x19 => CartridgeType::new(CartridgeMapper::MBC5, false, false, false, false),
+            0x1a => CartridgeType::new(CartridgeMapper::MBC5, true, false, false, false),
+            0x1b => CartridgeType::new(CartridgeMapper::MBC5, true, true, false, false),
+            0x1c => CartridgeType::new(CartridgeMapper::MBC5, false, false, false, true),
+            0x1d => CartridgeType::new(CartridgeMapper::MBC5, true, false, false, true),
+            0x1e => CartridgeType::new(CartridgeMapper::MBC5, true, true, false, true),
+            0x20 => CartridgeType::new(CartridgeMapper::MBC6, true, true, false, false),
+            0x22 => CartridgeType::new(CartridgeMapper::MBC7, true, true, false, true),
+            0xfc => CartridgeType::new(CartridgeMapper::POCKETCAMERA, true, true, false, false),
+            0xfd => CartridgeType::new(CartridgeMapper::TAMA5, true, true, true, false),
+            0xfe => CartridgeType::new(CartridgeMapper::HUC3, true, true, true, false),
+            0xff => CartridgeType::new(CartridgeMapper::HUC1, true, true, false, false),
             _    => CartridgeType::new(CartridgeMapper::INVALID, false, false, false, false)
         }
     }
@@ -206,14 +549,236 @@ impl Cartridge {
         println!("Title: {}", self.get_title());
         println!("Type: {:#?}", self.get_type());
 
-        let rom_size = self.get_rom_size().unwrap();
-        let rom_banks = self.get_rom_banks().unwrap();
-        println!("ROM Size: {}kb ({} banks)", rom_size / 0x400, rom_banks);
+        match (self.get_rom_size(), self.get_rom_banks()) {
+            (Ok(rom_size), Ok(rom_banks)) => println!("ROM Size: {}kb ({} banks)", rom_size / 0x400, rom_banks),
+            _ => println!("ROM Size: invalid ({:#04x})", self.header(0x148)),
+        }
 
-        let ram_size = self.get_ram_size().unwrap();
-        let ram_banks = self.get_ram_banks().unwrap();
-        println!("RAM Size: {}kb ({} banks)", ram_size / 0x400, ram_banks);
+        match (self.get_ram_size(), self.get_ram_banks()) {
+            (Ok(ram_size), Ok(ram_banks)) => println!("RAM Size: {}kb ({} banks)", ram_size / 0x400, ram_banks),
+            _ => println!("RAM Size: invalid ({:#04x})", self.header(0x149)),
+        }
 
         println!("Language: {:#?}", self.get_language());
+        println!("Header Checksum: {:#04x}", self.get_header_checksum());
+        println!("Global Checksum: {:#06x}", self.get_global_checksum());
+
+        let validation = self.validate_header();
+
+        if !validation.logo {
+            println!("WARN: cartridge logo does not match the Nintendo logo");
+        }
+
+        if !validation.header_checksum {
+            println!("WARN: header checksum is {:#04x}, expected {:#04x}", self.get_header_checksum(), self.compute_header_checksum());
+        }
+
+        if !validation.global_checksum {
+            println!("WARN: global checksum is {:#06x}, expected {:#06x}", self.get_global_checksum(), self.compute_global_checksum());
+        }
+
+        if let Some(footer) = self.gbx {
+            println!("GBX Footer: v{}.{} {} ({}kb ROM, {}kb RAM)", footer.major_version, footer.minor_version, footer.mapper_name(), footer.rom_size / 0x400, footer.ram_size / 0x400);
+        }
     }
-}
\ No newline at end of file
+}
+
+fn is_mmm01_type(value: u8) -> bool {
+    (0x0b..=0x0d).contains(&value)
+}
+
+fn mmm01_header_offset(buffer: &mut [u8]) -> usize {
+    if buffer.len() < MMM01_MENU_SIZE * 2 {
+        return 0;
+    }
+
+    // the menu sits in the last 32kb on the real chip, some dumps put it first instead
+    if is_mmm01_type(buffer[0x147]) {
+        buffer.rotate_left(MMM01_MENU_SIZE);
+    }
+
+    let header_offset = buffer.len() - MMM01_MENU_SIZE;
+
+    if is_mmm01_type(buffer[header_offset + 0x147]) {
+        header_offset
+    } else {
+        0
+    }
+}
+
+pub fn unscramble_sachen(address: u16) -> u16 {
+    (address & 0xffac) | ((address & 0x01) << 6) | ((address & 0x40) >> 6) | ((address & 0x02) << 3) | ((address & 0x10) >> 3)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_roms_do_not_panic() {
+        for &length in [0x00, 0x14f, 0x150, 0x180, 0x1ff].iter() {
+            for &type_byte in [0x00, 0x01, 0x13, 0x77].iter() {
+                let mut rom = vec![0; length];
+
+                if length > 0x147 {
+                    rom[0x147] = type_byte;
+                }
+
+                let _ = Cartridge::from_bytes(rom);
+            }
+        }
+    }
+
+    fn mbc1_image(logos: bool) -> Vec<u8> {
+        let mut rom = vec![0; MBC1M_ROM_SIZE];
+        rom[0x147] = 0x01;
+        rom[0x148] = 0x05;
+
+        if logos {
+            rom[0x104..0x134].copy_from_slice(&NINTENDO_LOGO);
+            rom[0x40104..0x40134].copy_from_slice(&NINTENDO_LOGO);
+        }
+
+        rom
+    }
+
+    #[test]
+    fn multicart_needs_the_logo_in_both_games() {
+        let cartridge = Cartridge::from_bytes(mbc1_image(true)).unwrap();
+        assert_eq!(cartridge.get_type().get_mapper(), CartridgeMapper::MBC1M);
+    }
+
+    #[test]
+    fn plain_mbc1_image_stays_mbc1() {
+        let cartridge = Cartridge::from_bytes(mbc1_image(false)).unwrap();
+        assert_eq!(cartridge.get_type().get_mapper(), CartridgeMapper::MBC1);
+
+        let mut rom = mbc1_image(false);
+        rom[0x104..0x134].copy_from_slice(&NINTENDO_LOGO);
+
+        let cartridge = Cartridge::from_bytes(rom).unwrap();
+        assert_eq!(cartridge.get_type().get_mapper(), CartridgeMapper::MBC1);
+    }
+
+    #[test]
+    fn mmm01_without_a_game_is_rejected() {
+        let mut rom = vec![0; MMM01_MENU_SIZE];
+        rom[0x147] = 0x0b;
+
+        match Cartridge::from_bytes(rom) {
+            Err(LoadError::INVALIDMMM01SIZE(0x8000)) => {},
+            _ => panic!("expected an invalid MMM01 size error"),
+        }
+    }
+
+    fn detected_mapper(rom: Vec<u8>) -> CartridgeMapper {
+        Cartridge::from_bytes(rom).unwrap().get_type().get_mapper()
+    }
+
+    fn unlicensed_image(length: usize, type_byte: u8, code: &[u8]) -> Vec<u8> {
+        let mut rom = vec![0; length];
+        rom[0x147] = type_byte;
+        rom[0x150..0x150 + code.len()].copy_from_slice(code);
+        rom
+    }
+
+    #[test]
+    fn sachen_is_detected_from_the_scrambled_logo() {
+        for &(offset, mapper) in [(0x80, CartridgeMapper::SACHENMMC1), (0x00, CartridgeMapper::SACHENMMC2)].iter() {
+            let mut rom = unlicensed_image(0x10000, 0x00, &[]);
+
+            for (i, &byte) in NINTENDO_LOGO.iter().enumerate() {
+                rom[unscramble_sachen((0x104 + i as u16) | offset) as usize] = byte;
+            }
+
+            assert_eq!(detected_mapper(rom), mapper);
+        }
+    }
+
+    #[test]
+    fn wisdom_tree_is_detected_from_its_name() {
+        assert_eq!(detected_mapper(unlicensed_image(0x10000, 0x00, b"WISDOM TREE")), CartridgeMapper::WISDOMTREE);
+        assert_eq!(detected_mapper(unlicensed_image(0x10000, 0x77, b"WISDOM\x00TREE")), CartridgeMapper::WISDOMTREE);
+    }
+
+    #[test]
+    fn m161_needs_a_logo_in_every_game() {
+        let mut rom = unlicensed_image(M161_GAME_SIZE * 8, 0x00, &[]);
+
+        for game in 1..8 {
+            let address = (game * M161_GAME_SIZE) + 0x104;
+            rom[address..address + NINTENDO_LOGO.len()].copy_from_slice(&NINTENDO_LOGO);
+        }
+
+        assert_eq!(detected_mapper(rom.clone()), CartridgeMapper::M161);
+
+        rom[(7 * M161_GAME_SIZE) + 0x104] = 0x00;
+        assert_eq!(detected_mapper(rom), CartridgeMapper::NONE);
+    }
+
+    #[test]
+    fn clones_are_detected_from_their_bank_writes() {
+        assert_eq!(detected_mapper(unlicensed_image(0x10000, 0x77, &[0xea, 0x00, 0x30])), CartridgeMapper::MBC5);
+        assert_eq!(detected_mapper(unlicensed_image(0x10000, 0x77, &[0xea, 0x00, 0x20])), CartridgeMapper::MBC1);
+        assert_eq!(detected_mapper(unlicensed_image(0x10000, 0x00, &[0xea, 0x00, 0x21])), CartridgeMapper::MBC1);
+        assert_eq!(detected_mapper(unlicensed_image(0x8000, 0x77, &[0xea, 0x00, 0x30])), CartridgeMapper::INVALID);
+    }
+
+    #[test]
+    fn roms_without_a_signature_are_left_alone() {
+        assert_eq!(detected_mapper(unlicensed_image(0x10000, 0x77, &[])), CartridgeMapper::INVALID);
+        assert_eq!(detected_mapper(unlicensed_image(0x10000, 0x00, &[])), CartridgeMapper::NONE);
+        assert_eq!(detected_mapper(unlicensed_image(0x10000, 0x01, &[0xea, 0x00, 0x30])), CartridgeMapper::MBC1);
+    }
+
+    fn tetris_header() -> Vec<u8> {
+        let mut rom = vec![0; 0x8000];
+        rom[0x104..0x134].copy_from_slice(&NINTENDO_LOGO);
+        rom[0x134..0x13a].copy_from_slice(b"TETRIS");
+        rom[0x14b] = 0x01;
+        rom[0x14c] = 0x01;
+        rom[0x14d] = 0x0a;
+        rom
+    }
+
+    #[test]
+    fn header_checksum_matches_known_header() {
+        let cartridge = Cartridge::from_bytes(tetris_header()).unwrap();
+
+        assert_eq!(cartridge.compute_header_checksum(), 0x0a);
+        assert!(cartridge.validate_header().header_checksum);
+        assert!(cartridge.validate_header().logo);
+    }
+
+    #[test]
+    fn global_checksum_skips_its_own_bytes_and_wraps() {
+        let mut rom = vec![0xff; 0x8000];
+        rom[0x147..0x14a].copy_from_slice(&[0x00, 0x00, 0x00]);
+        rom[0x14e] = 0x12;
+        rom[0x14f] = 0x34;
+
+        let cartridge = Cartridge::from_bytes(rom).unwrap();
+        let expected = (0xff * (0x8000 - 5)) as u16;
+
+        assert_eq!(cartridge.get_global_checksum(), 0x1234);
+        assert_eq!(cartridge.compute_global_checksum(), expected);
+        assert!(!cartridge.validate_header().global_checksum);
+    }
+
+    #[test]
+    fn corrupt_header_is_reported() {
+        let mut rom = tetris_header();
+        rom[0x105] = 0x00;
+        rom[0x140] = 0x20;
+
+        let validation = Cartridge::from_bytes(rom).unwrap().validate_header();
+
+        assert!(!validation.logo);
+        assert!(!validation.header_checksum);
+        assert!(!validation.is_valid());
+    }
+}
@@ -1,4 +1,14 @@
-use sdl2::keyboard::Keycode;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Button {
+    A,
+    B,
+    START,
+    SELECT,
+    UP,
+    DOWN,
+    LEFT,
+    RIGHT,
+}
 
 pub struct Controller {
     button_select: bool,
@@ -16,6 +26,12 @@ pub struct Controller {
     interrupt: bool,
 }
 
+impl Default for Controller {
+    fn default() -> Controller {
+        Controller::new()
+    }
+}
+
 impl Controller {
     pub fn new() -> Controller {
         Controller {
@@ -72,23 +88,21 @@ impl Controller {
         self.direction_select = (value & 0x10) != 0;
     }
 
-    pub fn set(&mut self, keycode: Keycode, state: bool) {
-        let mut valid = true;
-        let mut prev = true;
+    pub fn set(&mut self, button: Button, state: bool) {
+        let prev;
 
-        match keycode {
-            Keycode::A => {prev = self.a; self.a = !state},
-            Keycode::S =>  {prev = self.b; self.b = !state},
-            Keycode::Z =>  {prev = self.start; self.start = !state},
-            Keycode::X =>  {prev = self.select; self.select = !state},
-            Keycode::Up =>  {prev = self.up; self.up = !state},
-            Keycode::Down =>  {prev = self.down; self.down = !state},
-            Keycode::Left =>  {prev = self.left; self.left = !state},
-            Keycode::Right =>  {prev = self.right; self.right = !state},
-            _ => valid = false,
+        match button {
+            Button::A => {prev = self.a; self.a = !state},
+            Button::B => {prev = self.b; self.b = !state},
+            Button::START => {prev = self.start; self.start = !state},
+            Button::SELECT => {prev = self.select; self.select = !state},
+            Button::UP => {prev = self.up; self.up = !state},
+            Button::DOWN => {prev = self.down; self.down = !state},
+            Button::LEFT => {prev = self.left; self.left = !state},
+            Button::RIGHT => {prev = self.right; self.right = !state},
         };
 
-        if valid && prev && state {
+        if prev && state {
             self.interrupt = true;
         }
     }
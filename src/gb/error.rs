@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum LoadError {
+    IO(io::Error),
+    TRUNCATED(usize),
+    INVALIDROMSIZE(u8),
+    INVALIDRAMSIZE(u8),
+    UNSUPPORTEDMAPPER(u8),
+    SIZEMISMATCH { expected: usize, actual: usize },
+    UNSUPPORTEDGBXVERSION(u32, u32),
+    INVALIDGBXFOOTER(String),
+    UNSUPPORTEDGBXMAPPER(String),
+    INVALIDBOOTROM(usize),
+    INVALIDMMM01SIZE(usize),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::IO(ref e) => write!(f, "{}", e),
+            LoadError::TRUNCATED(size) => write!(f, "file too small for a cartridge header ({} bytes)", size),
+            LoadError::INVALIDROMSIZE(value) => write!(f, "invalid cartridge ROM size 0x{:02x}", value),
+            LoadError::INVALIDRAMSIZE(value) => write!(f, "invalid cartridge RAM size 0x{:02x}", value),
+            LoadError::UNSUPPORTEDMAPPER(value) => write!(f, "unsupported cartridge type 0x{:02x}", value),
+            LoadError::SIZEMISMATCH { expected, actual } => {
+                write!(f, "cartridge header declares {} bytes of ROM but the file has {}", expected, actual)
+            },
+            LoadError::UNSUPPORTEDGBXVERSION(major, minor) => write!(f, "unsupported GBX footer version {}.{}", major, minor),
+            LoadError::INVALIDGBXFOOTER(ref reason) => write!(f, "invalid GBX footer: {}", reason),
+            LoadError::UNSUPPORTEDGBXMAPPER(ref name) => write!(f, "unsupported GBX mapper \"{}\"", name),
+            LoadError::INVALIDBOOTROM(size) => write!(f, "boot ROM must be 256 bytes, not {}", size),
+            LoadError::INVALIDMMM01SIZE(size) => write!(f, "MMM01 cartridges need a menu and at least one 32kb game, not {} bytes", size),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            LoadError::IO(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::IO(e)
+    }
+}
@@ -0,0 +1,148 @@
+use super::error::LoadError;
+
+pub const GBX_MAGIC: &[u8; 4] = b"GBX!";
+pub const GBX_FOOTER_SIZE: usize = 0x40;
+pub const GBX_MAJOR_VERSION: u32 = 1;
+pub const GBX_MAX_RAM_SIZE: usize = 0x20000;
+
+#[derive(Clone, Copy, Debug)]
+pub struct GbxFooter {
+    pub mapper_id: [u8; 4],
+    pub battery: bool,
+    pub rumble: bool,
+    pub timer: bool,
+    pub rom_size: usize,
+    pub ram_size: usize,
+    pub major_version: u32,
+    pub minor_version: u32,
+}
+
+impl GbxFooter {
+    pub fn mapper_name(&self) -> String {
+        String::from_utf8_lossy(&self.mapper_id).trim_end_matches(&['\0', ' '][..]).to_string()
+    }
+}
+
+fn read_u32(buffer: &[u8], address: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&buffer[address..address + 4]);
+    u32::from_be_bytes(bytes)
+}
+
+pub fn split_footer(buffer: &mut Vec<u8>) -> Result<Option<GbxFooter>, LoadError> {
+    let length = buffer.len();
+
+    if length < GBX_FOOTER_SIZE || &buffer[length - 4..] != GBX_MAGIC {
+        return Ok(None);
+    }
+
+    let footer_size = read_u32(buffer, length - 0x10) as usize;
+    let major_version = read_u32(buffer, length - 0x0c);
+    let minor_version = read_u32(buffer, length - 0x08);
+
+    if major_version != GBX_MAJOR_VERSION {
+        return Err(LoadError::UNSUPPORTEDGBXVERSION(major_version, minor_version));
+    }
+
+    if footer_size < GBX_FOOTER_SIZE || footer_size > length {
+        return Err(LoadError::INVALIDGBXFOOTER(format!("footer size {}", footer_size)));
+    }
+
+    let start = length - footer_size;
+    let mut mapper_id = [0; 4];
+    mapper_id.copy_from_slice(&buffer[start..start + 4]);
+
+    let footer = GbxFooter {
+        mapper_id,
+        battery: buffer[start + 0x04] != 0,
+        rumble: buffer[start + 0x05] != 0,
+        timer: buffer[start + 0x06] != 0,
+        rom_size: read_u32(buffer, start + 0x08) as usize,
+        ram_size: read_u32(buffer, start + 0x0c) as usize,
+        major_version,
+        minor_version,
+    };
+
+    if footer.rom_size < 0x8000 || !footer.rom_size.is_power_of_two() || footer.rom_size > start {
+        return Err(LoadError::INVALIDGBXFOOTER(format!("ROM size {} with {} bytes of data", footer.rom_size, start)));
+    }
+
+    if footer.ram_size > GBX_MAX_RAM_SIZE {
+        return Err(LoadError::INVALIDGBXFOOTER(format!("RAM size {} is larger than {}", footer.ram_size, GBX_MAX_RAM_SIZE)));
+    }
+
+    buffer.truncate(start);
+
+    Ok(Some(footer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn footer(footer_size: u32, major_version: u32, rom_size: u32, ram_size: u32) -> Vec<u8> {
+        let mut buffer = vec![0; 0x8000];
+        let mut footer = vec![0; GBX_FOOTER_SIZE];
+
+        footer[0x00..0x04].copy_from_slice(b"MBC5");
+        footer[0x04] = 1;
+        footer[0x08..0x0c].copy_from_slice(&rom_size.to_be_bytes());
+        footer[0x0c..0x10].copy_from_slice(&ram_size.to_be_bytes());
+        footer[0x30..0x34].copy_from_slice(&footer_size.to_be_bytes());
+        footer[0x34..0x38].copy_from_slice(&major_version.to_be_bytes());
+        footer[0x3c..0x40].copy_from_slice(GBX_MAGIC);
+
+        buffer.extend(footer);
+        buffer
+    }
+
+    #[test]
+    fn valid_footer_is_split_off() {
+        let mut buffer = footer(0x40, 1, 0x8000, 0x2000);
+        let footer = split_footer(&mut buffer).unwrap().unwrap();
+
+        assert_eq!(buffer.len(), 0x8000);
+        assert_eq!(footer.mapper_name(), "MBC5");
+        assert!(footer.battery);
+        assert_eq!(footer.rom_size, 0x8000);
+        assert_eq!(footer.ram_size, 0x2000);
+    }
+
+    #[test]
+    fn missing_magic_is_not_a_footer() {
+        let mut buffer = vec![0; 0x8000];
+
+        assert!(split_footer(&mut buffer).unwrap().is_none());
+        assert_eq!(buffer.len(), 0x8000);
+    }
+
+    #[test]
+    fn unsupported_version_is_rejected() {
+        match split_footer(&mut footer(0x40, 2, 0x8000, 0)) {
+            Err(LoadError::UNSUPPORTEDGBXVERSION(2, 0)) => {},
+            _ => panic!("expected an unsupported version error"),
+        }
+    }
+
+    #[test]
+    fn bad_sizes_are_rejected() {
+        let sizes = [
+            (0x20, 0x8000, 0),
+            (0x10000, 0x8000, 0),
+            (0x40, 0x8000, 0x40000),
+            (0x40, 0x8000, 0xffffffff),
+            (0x40, 0, 0),
+            (0x40, 0x200, 0),
+            (0x40, 0x6000, 0),
+            (0x40, 0xc000, 0),
+            (0x40, 0x10000, 0),
+        ];
+
+        for &(footer_size, rom_size, ram_size) in sizes.iter() {
+            match split_footer(&mut footer(footer_size, 1, rom_size, ram_size)) {
+                Err(LoadError::INVALIDGBXFOOTER(_)) => {},
+                _ => panic!("expected an invalid footer error for {:#x}/{:#x}/{:#x}", footer_size, rom_size, ram_size),
+            }
+        }
+    }
+}
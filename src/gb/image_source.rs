@@ -0,0 +1,227 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const IMAGE_SOURCE_WIDTH: usize = 128;
+pub const IMAGE_SOURCE_HEIGHT: usize = 112;
+
+pub trait ImageSource {
+    fn capture(&mut self, image: &mut [u8]);
+}
+
+pub struct TestPatternImageSource {
+    frame: usize,
+}
+
+impl TestPatternImageSource {
+    pub fn new() -> TestPatternImageSource {
+        TestPatternImageSource {
+            frame: 0,
+        }
+    }
+}
+
+impl Default for TestPatternImageSource {
+    fn default() -> Self {
+        TestPatternImageSource::new()
+    }
+}
+
+impl ImageSource for TestPatternImageSource {
+    fn capture(&mut self, image: &mut [u8]) {
+        for y in 0..IMAGE_SOURCE_HEIGHT {
+            for x in 0..IMAGE_SOURCE_WIDTH {
+                let gradient = (x * 255 / (IMAGE_SOURCE_WIDTH - 1)) as u8;
+                let bar = ((x + self.frame * 4) / 16) % 8 == 0;
+                let checker = ((x / 8) + (y / 8)) % 2 == 0;
+
+                image[(y * IMAGE_SOURCE_WIDTH) + x] = match (y < IMAGE_SOURCE_HEIGHT / 2, bar) {
+                    (_, true) => 0x00,
+                    (true, false) => gradient,
+                    (false, false) => if checker { 0xff } else { 0x40 },
+                };
+            }
+        }
+
+        self.frame += 1;
+    }
+}
+
+pub struct StaticImageSource {
+    image: Vec<u8>,
+}
+
+impl StaticImageSource {
+    pub fn new(filepath: &Path) -> io::Result<StaticImageSource> {
+        Ok(StaticImageSource {
+            image: load_image(filepath)?,
+        })
+    }
+}
+
+impl ImageSource for StaticImageSource {
+    fn capture(&mut self, image: &mut [u8]) {
+        image.copy_from_slice(&self.image);
+    }
+}
+
+pub struct FileSequenceImageSource {
+    filepaths: Vec<PathBuf>,
+    index: usize,
+}
+
+impl FileSequenceImageSource {
+    pub fn new(directory: &Path) -> io::Result<FileSequenceImageSource> {
+        let mut filepaths = Vec::new();
+
+        for entry in fs::read_dir(directory)? {
+            let filepath = entry?.path();
+            let extension = filepath.extension().and_then(|extension| extension.to_str()).unwrap_or("");
+
+            if ["pgm", "ppm", "pnm"].contains(&extension.to_lowercase().as_str()) {
+                filepaths.push(filepath);
+            }
+        }
+
+        if filepaths.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "no .pgm or .ppm images in directory"));
+        }
+
+        filepaths.sort();
+
+        Ok(FileSequenceImageSource {
+            filepaths,
+            index: 0,
+        })
+    }
+}
+
+impl ImageSource for FileSequenceImageSource {
+    fn capture(&mut self, image: &mut [u8]) {
+        let filepath = &self.filepaths[self.index];
+
+        match load_image(filepath) {
+            Ok(frame) => image.copy_from_slice(&frame),
+            Err(e) => println!("WARN: failed to load camera image {}: {}", filepath.display(), e),
+        }
+
+        self.index = (self.index + 1) % self.filepaths.len();
+    }
+}
+
+pub fn open(source: &str) -> io::Result<Box<dyn ImageSource + Send>> {
+    let path = Path::new(source);
+
+    if source == "pattern" {
+        Ok(Box::new(TestPatternImageSource::new()))
+    } else if path.is_dir() {
+        Ok(Box::new(FileSequenceImageSource::new(path)?))
+    } else {
+        Ok(Box::new(StaticImageSource::new(path)?))
+    }
+}
+
+fn load_image(filepath: &Path) -> io::Result<Vec<u8>> {
+    let data = fs::read(filepath)?;
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let mut fields = Vec::new();
+    let mut position = 0;
+
+    while fields.len() < 4 {
+        while position < data.len() && data[position].is_ascii_whitespace() {
+            position += 1;
+        }
+
+        if position < data.len() && data[position] == b'#' {
+            while position < data.len() && data[position] != b'\n' {
+                position += 1;
+            }
+
+            continue;
+        }
+
+        let start = position;
+
+        while position < data.len() && !data[position].is_ascii_whitespace() {
+            position += 1;
+        }
+
+        if start == position {
+            return Err(invalid("truncated image header"));
+        }
+
+        fields.push(String::from_utf8_lossy(&data[start..position]).into_owned());
+    }
+
+    let channels = match fields[0].as_str() {
+        "P5" => 1,
+        "P6" => 3,
+        _ => return Err(invalid("only binary .pgm and .ppm images are supported")),
+    };
+
+    let width: usize = fields[1].parse().map_err(|_| invalid("invalid image width"))?;
+    let height: usize = fields[2].parse().map_err(|_| invalid("invalid image height"))?;
+    let maximum: usize = fields[3].parse().map_err(|_| invalid("invalid image maximum value"))?;
+
+    if width == 0 || height == 0 || maximum == 0 || maximum > 255 {
+        return Err(invalid("unsupported image dimensions or depth"));
+    }
+
+    let pixels = &data[(position + 1).min(data.len())..];
+    let length = width.checked_mul(height).and_then(|length| length.checked_mul(channels)).ok_or_else(|| invalid("image dimensions are too large"))?;
+
+    if pixels.len() < length {
+        return Err(invalid("truncated image data"));
+    }
+
+    let mut image = vec![0u8; IMAGE_SOURCE_WIDTH * IMAGE_SOURCE_HEIGHT];
+
+    for y in 0..IMAGE_SOURCE_HEIGHT {
+        for x in 0..IMAGE_SOURCE_WIDTH {
+            let source = (((y * height / IMAGE_SOURCE_HEIGHT) * width) + (x * width / IMAGE_SOURCE_WIDTH)) * channels;
+            let sum: usize = pixels[source..source + channels].iter().map(|&value| value as usize).sum();
+
+            image[(y * IMAGE_SOURCE_WIDTH) + x] = ((sum / channels) * 255 / maximum) as u8;
+        }
+    }
+
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn load(name: &str, data: &[u8]) -> io::Result<Vec<u8>> {
+        let filepath = env::temp_dir().join(format!("rgb-image-source-{}-{}.pgm", name, std::process::id()));
+        fs::write(&filepath, data)?;
+
+        let image = load_image(&filepath);
+        fs::remove_file(&filepath)?;
+        image
+    }
+
+    #[test]
+    fn greyscale_image_is_scaled_to_the_sensor() {
+        let mut data = b"P5\n# two columns\n2 1\n255\n".to_vec();
+        data.extend_from_slice(&[0x00, 0xff]);
+
+        let image = load("scaled", &data).unwrap();
+
+        assert_eq!(image[0], 0x00);
+        assert_eq!(image[IMAGE_SOURCE_WIDTH - 1], 0xff);
+        assert_eq!(image[(IMAGE_SOURCE_HEIGHT - 1) * IMAGE_SOURCE_WIDTH], 0x00);
+    }
+
+    #[test]
+    fn oversized_dimensions_are_invalid_data() {
+        let data = format!("P6 {} {} 255\n", usize::MAX / 2, 3);
+
+        match load("oversized", data.as_bytes()) {
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData => {},
+            _ => panic!("expected an invalid data error"),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use super::super::cartridge::Cartridge;
+use super::super::image_source::{ImageSource, TestPatternImageSource, IMAGE_SOURCE_WIDTH, IMAGE_SOURCE_HEIGHT};
+use super::Mapper;
+
+pub const CAMERA_IMAGE_ADDRESS: usize = 0x0100;
+
+const CAMERA_REGISTER_BANK: u8 = 0x10;
+const CAMERA_REGISTER_COUNT: usize = 0x36;
+const CAMERA_MATRIX_ADDRESS: usize = 0x06;
+
+const CAMERA_EDGE_RATIOS: [i32; 8] = [2, 3, 4, 5, 8, 12, 16, 20];
+
+pub struct MapperPocketCamera {
+    cartridge: Cartridge,
+    ram_enable: bool,
+    rom_bank: u8,
+    ram_bank: u8,
+    registers: [u8; CAMERA_REGISTER_COUNT],
+    capture_cycles: usize,
+    image_source: Box<dyn ImageSource + Send>,
+}
+
+impl MapperPocketCamera {
+    pub fn new(cartridge: Cartridge) -> MapperPocketCamera {
+        MapperPocketCamera {
+            cartridge,
+            ram_enable: false,
+            rom_bank: 1,
+            ram_bank: 0,
+            registers: [0; CAMERA_REGISTER_COUNT],
+            capture_cycles: 0,
+            image_source: Box::new(TestPatternImageSource::new()),
+        }
+    }
+
+    fn exposure(&self) -> i32 {
+        ((self.registers[0x02] as i32) << 8) | self.registers[0x03] as i32
+    }
+
+    fn start_capture(&mut self) {
+        let n = (self.registers[0x01] & 0x80) != 0;
+        let cycles = 32446 + if n { 0 } else { 512 } + (16 * self.exposure() as usize);
+
+        self.capture_cycles = cycles * 4;
+    }
+
+    fn finish_capture(&mut self) {
+        let mut sensor = vec![0u8; IMAGE_SOURCE_WIDTH * IMAGE_SOURCE_HEIGHT];
+        self.image_source.capture(&mut sensor);
+
+        let exposure = self.exposure();
+        let invert = (self.registers[0x04] & 0x08) != 0;
+        let edge_enable = (self.registers[0x04] & 0x80) != 0;
+        let edge_ratio = CAMERA_EDGE_RATIOS[((self.registers[0x04] >> 4) & 0x07) as usize];
+
+        let exposed = |x: usize, y: usize| -> i32 {
+            let x = x.min(IMAGE_SOURCE_WIDTH - 1);
+            let y = y.min(IMAGE_SOURCE_HEIGHT - 1);
+            let value = sensor[(y * IMAGE_SOURCE_WIDTH) + x] as i32;
+
+            (value * exposure / 0x0800).min(0xff)
+        };
+
+        for y in 0..IMAGE_SOURCE_HEIGHT {
+            for x in 0..IMAGE_SOURCE_WIDTH {
+                let mut value = exposed(x, y);
+
+                if edge_enable {
+                    let neighbours = exposed(x.saturating_sub(1), y) + exposed(x + 1, y) + exposed(x, y.saturating_sub(1)) + exposed(x, y + 1);
+                    value += ((value * 4) - neighbours) * edge_ratio / 4;
+                }
+
+                let mut value = value.clamp(0x00, 0xff) as u8;
+
+                if invert {
+                    value = 0xff - value;
+                }
+
+                let matrix = CAMERA_MATRIX_ADDRESS + ((((y & 3) * 4) + (x & 3)) * 3);
+                let shade = if value < self.registers[matrix] {
+                    3
+                } else if value < self.registers[matrix + 1] {
+                    2
+                } else if value < self.registers[matrix + 2] {
+                    1
+                } else {
+                    0
+                };
+
+                let tile = ((y / 8) * (IMAGE_SOURCE_WIDTH / 8)) + (x / 8);
+                let address = CAMERA_IMAGE_ADDRESS + (tile * 16) + ((y & 7) * 2);
+                let bit = 7 - (x & 7);
+
+                for plane in 0..2 {
+                    let mut byte = self.cartridge.read_ram(address + plane);
+
+                    byte &= !(1 << bit);
+                    byte |= ((shade >> plane) & 0x01) << bit;
+
+                    self.cartridge.write_ram(address + plane, byte);
+                }
+            }
+        }
+
+        self.registers[0x00] &= !0x01;
+    }
+}
+
+impl Mapper for MapperPocketCamera {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank as usize
+        } else {
+            0
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        if self.ram_bank & CAMERA_REGISTER_BANK != 0 {
+            return match address & 0x7f {
+                0x00 => self.registers[0x00] & 0x07,
+                _ => 0x00,
+            };
+        }
+
+        if !self.ram_enable {
+            return 0xff;
+        }
+
+        if self.capture_cycles > 0 || !self.cartridge.has_ram() {
+            return 0x00;
+        }
+
+        let ram_size = self.cartridge.get_ram_length() - 1;
+        let bank_address = (self.ram_bank as usize * 0x2000) | (address as usize & 0x1fff);
+        self.cartridge.read_ram(bank_address & ram_size)
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1fff => {
+                self.ram_enable = (value & 0x0f) == 0x0a;
+            },
+            0x2000..=0x3fff => {
+                self.rom_bank = value & 0x3f;
+            },
+            0x4000..=0x5fff => {
+                self.ram_bank = value & 0x1f;
+            },
+            0x6000..=0x7fff => {},
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.ram_bank & CAMERA_REGISTER_BANK != 0 {
+            let register = address as usize & 0x7f;
+
+            if register == 0x00 {
+                self.registers[0x00] = value & 0x07;
+
+                if value & 0x01 != 0 && self.capture_cycles == 0 {
+                    self.start_capture();
+                }
+            } else if register < CAMERA_REGISTER_COUNT {
+                self.registers[register] = value;
+            }
+
+            return;
+        }
+
+        if !self.ram_enable || self.capture_cycles > 0 || !self.cartridge.has_ram() {
+            return;
+        }
+
+        let ram_size = self.cartridge.get_ram_length() - 1;
+        let bank_address = (self.ram_bank as usize * 0x2000) | (address as usize & 0x1fff);
+        self.cartridge.write_ram(bank_address & ram_size, value);
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+
+    fn tick(&mut self, cycles: usize) {
+        if self.capture_cycles > 0 {
+            self.capture_cycles = self.capture_cycles.saturating_sub(cycles);
+
+            if self.capture_cycles == 0 {
+                self.finish_capture();
+            }
+        }
+    }
+
+    fn set_image_source(&mut self, image_source: Box<dyn ImageSource + Send>) {
+        self.image_source = image_source;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct SolidImageSource(u8);
+
+    impl ImageSource for SolidImageSource {
+        fn capture(&mut self, image: &mut [u8]) {
+            for pixel in image.iter_mut() {
+                *pixel = self.0;
+            }
+        }
+    }
+
+    fn mapper(shade: u8) -> MapperPocketCamera {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0xfc;
+        rom[0x149] = 0x04;
+
+        let mut mapper = MapperPocketCamera::new(Cartridge::from_bytes(rom).unwrap());
+        mapper.set_image_source(Box::new(SolidImageSource(shade)));
+        mapper
+    }
+
+    fn capture(mapper: &mut MapperPocketCamera) {
+        mapper.write_rom(0x4000, CAMERA_REGISTER_BANK);
+        mapper.write_ram(0xa002, 0x08);
+        mapper.write_ram(0xa003, 0x00);
+
+        for matrix in 0..16 {
+            mapper.write_ram(0xa000 + (CAMERA_MATRIX_ADDRESS + (matrix * 3)) as u16, 0x40);
+            mapper.write_ram(0xa000 + (CAMERA_MATRIX_ADDRESS + (matrix * 3) + 1) as u16, 0x80);
+            mapper.write_ram(0xa000 + (CAMERA_MATRIX_ADDRESS + (matrix * 3) + 2) as u16, 0xc0);
+        }
+
+        mapper.write_ram(0xa000, 0x01);
+        assert_eq!(mapper.read_ram(0xa000), 0x01);
+
+        while mapper.read_ram(0xa000) & 0x01 != 0 {
+            mapper.tick(0x1000);
+        }
+
+        mapper.write_rom(0x4000, 0x00);
+    }
+
+    #[test]
+    fn capture_is_written_to_ram_as_tiles() {
+        let mut dark = mapper(0x00);
+        capture(&mut dark);
+
+        dark.write_rom(0x0000, 0x0a);
+        assert_eq!(dark.read_ram(0xa000), 0x00);
+        assert_eq!(dark.read_ram(0xa000 + CAMERA_IMAGE_ADDRESS as u16), 0xff);
+        assert_eq!(dark.read_ram(0xa001 + CAMERA_IMAGE_ADDRESS as u16), 0xff);
+        assert_eq!(dark.read_ram(0xa000 + (CAMERA_IMAGE_ADDRESS + 0xdff) as u16), 0xff);
+
+        let mut bright = mapper(0xa0);
+        capture(&mut bright);
+
+        bright.write_rom(0x0000, 0x0a);
+        assert_eq!(bright.read_ram(0xa000 + CAMERA_IMAGE_ADDRESS as u16), 0xff);
+        assert_eq!(bright.read_ram(0xa001 + CAMERA_IMAGE_ADDRESS as u16), 0x00);
+    }
+
+    #[test]
+    fn ram_reads_need_ram_enable() {
+        let mut mapper = mapper(0x00);
+
+        mapper.write_rom(0x0000, 0x0a);
+        mapper.write_ram(0xa123, 0x5a);
+        assert_eq!(mapper.read_ram(0xa123), 0x5a);
+
+        mapper.write_rom(0x0000, 0x00);
+        assert_eq!(mapper.read_ram(0xa123), 0xff);
+    }
+}
@@ -0,0 +1,160 @@
+use super::super::cartridge::Cartridge;
+use super::Mapper;
+
+pub struct MapperHuC1 {
+    cartridge: Cartridge,
+    ir_mode: bool,
+    ir_led: bool,
+    ir_input: bool,
+    rom_bank: u8,
+    ram_bank: u8,
+}
+
+impl MapperHuC1 {
+    pub fn new(cartridge: Cartridge) -> MapperHuC1 {
+        MapperHuC1 {
+            cartridge,
+            ir_mode: false,
+            ir_led: false,
+            ir_input: false,
+            rom_bank: 1,
+            ram_bank: 0,
+        }
+    }
+}
+
+impl Mapper for MapperHuC1 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank as usize
+        } else {
+            0
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        if self.ir_mode {
+            return 0xc0 | self.ir_input as u8;
+        }
+
+        let address = address as usize;
+        let bank = self.ram_bank as usize;
+
+        let ram_size = self.cartridge.get_ram_length().wrapping_sub(1);
+        let bank_address = (bank * 0x2000) | (address & 0x1fff);
+
+        if self.cartridge.has_ram() {
+            self.cartridge.read_ram(bank_address & ram_size)
+        } else {
+            0xff
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1fff => {
+                self.ir_mode = (value & 0x0f) == 0x0e;
+            },
+            0x2000..=0x3fff => {
+                self.rom_bank = value & 0x3f;
+
+                if self.rom_bank == 0 {
+                    self.rom_bank = 0x01;
+                }
+            },
+            0x4000..=0x5fff => {
+                self.ram_bank = value & 0x03;
+            },
+            0x6000..=0x7fff => {},
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.ir_mode {
+            self.ir_led = (value & 0x01) != 0;
+            return;
+        }
+
+        let address = address as usize;
+        let bank = self.ram_bank as usize;
+
+        let ram_size = self.cartridge.get_ram_length().wrapping_sub(1);
+        let bank_address = (bank * 0x2000) | (address & 0x1fff);
+
+        if self.cartridge.has_ram() {
+            self.cartridge.write_ram(bank_address & ram_size, value);
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+
+    fn ir_led(&self) -> bool {
+        self.ir_led
+    }
+
+    fn set_ir_input(&mut self, light: bool) {
+        self.ir_input = light;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapper() -> MapperHuC1 {
+        let mut rom = vec![0; 0x100000];
+        rom[0x147] = 0xff;
+        rom[0x148] = 0x05;
+        rom[0x149] = 0x03;
+
+        for bank in 0..64 {
+            rom[bank * 0x4000 + 0x10] = bank as u8;
+        }
+
+        MapperHuC1::new(Cartridge::from_bytes(rom).unwrap())
+    }
+
+    #[test]
+    fn rom_bank_zero_selects_bank_one() {
+        let mut mapper = mapper();
+
+        mapper.write_rom(0x2000, 0x3f);
+        assert_eq!(mapper.read_rom(0x4010), 0x3f);
+
+        mapper.write_rom(0x2000, 0x00);
+        assert_eq!(mapper.read_rom(0x4010), 0x01);
+    }
+
+    #[test]
+    fn infrared_mode_replaces_ram() {
+        let mut mapper = mapper();
+
+        mapper.write_rom(0x0000, 0x0a);
+        mapper.write_ram(0xa000, 0x5a);
+
+        mapper.write_rom(0x0000, 0x0e);
+        assert_eq!(mapper.read_ram(0xa000), 0xc0);
+
+        mapper.set_ir_input(true);
+        assert_eq!(mapper.read_ram(0xa000), 0xc1);
+
+        mapper.write_ram(0xa000, 0x01);
+        assert!(mapper.ir_led());
+
+        mapper.write_rom(0x0000, 0x0a);
+        assert_eq!(mapper.read_ram(0xa000), 0x5a);
+        assert!(mapper.ir_led());
+    }
+}
@@ -0,0 +1,402 @@
+use super::super::cartridge::Cartridge;
+use super::super::rtc::{RtcClock, RtcMode, RTC_CYCLES_PER_SECOND};
+use super::Mapper;
+
+pub const HUC3_RTC_SAVE_SIZE: usize = 17;
+
+const HUC3_MINUTES_PER_DAY: u32 = 1440;
+const HUC3_CLOCK_MINUTES: u64 = HUC3_MINUTES_PER_DAY as u64 * 0x1000;
+const HUC3_ALARM_ADDRESS: usize = 0x10;
+const HUC3_ALARM_ENABLE_ADDRESS: usize = 0x16;
+
+#[derive(Clone, Copy, PartialEq)]
+enum HuC3Mode {
+    RAMREAD,
+    RAMWRITE,
+    COMMAND,
+    RESPONSE,
+    SEMAPHORE,
+    IR,
+}
+
+pub struct MapperHuC3 {
+    cartridge: Cartridge,
+    mode: HuC3Mode,
+    rom_bank: u8,
+    ram_bank: u8,
+
+    seconds: u64,
+    minutes: u16,
+    days: u16,
+    rtc_clock: RtcClock,
+    rtc_dirty: bool,
+
+    memory: [u8; 0x100],
+    address: u8,
+    response: u8,
+
+    tone_cycles: usize,
+
+    ir_led: bool,
+    ir_input: bool,
+}
+
+impl MapperHuC3 {
+    pub fn new(cartridge: Cartridge) -> MapperHuC3 {
+        MapperHuC3 {
+            cartridge,
+            mode: HuC3Mode::RAMREAD,
+            rom_bank: 1,
+            ram_bank: 0,
+
+            seconds: 0,
+            minutes: 0,
+            days: 0,
+            rtc_clock: RtcClock::new(),
+            rtc_dirty: false,
+
+            memory: [0; 0x100],
+            address: 0,
+            response: 0,
+
+            tone_cycles: 0,
+
+            ir_led: false,
+            ir_input: false,
+        }
+    }
+
+    fn update_rtc(&mut self) {
+        let elapsed = self.rtc_clock.elapsed();
+        self.advance(elapsed);
+    }
+
+    fn advance(&mut self, seconds: u64) {
+        if seconds == 0 {
+            return;
+        }
+
+        let total = self.seconds + seconds;
+        let elapsed_minutes = total / 60;
+        let start = self.clock_minutes();
+        let minutes = self.minutes as u64 + elapsed_minutes;
+
+        self.seconds = total % 60;
+        self.minutes = (minutes % HUC3_MINUTES_PER_DAY as u64) as u16;
+        self.days = ((self.days as u64 + (minutes / HUC3_MINUTES_PER_DAY as u64)) & 0xfff) as u16;
+
+        if self.alarm_enabled() && elapsed_minutes > 0 {
+            let (alarm_minutes, alarm_days) = self.alarm();
+            let alarm = ((alarm_days as u64 * HUC3_MINUTES_PER_DAY as u64) + alarm_minutes as u64) % HUC3_CLOCK_MINUTES;
+
+            // minutes from the one after start up to the alarm, so any jump that passes it rings
+            if (alarm + HUC3_CLOCK_MINUTES - start - 1) % HUC3_CLOCK_MINUTES < elapsed_minutes {
+                self.tone_cycles = RTC_CYCLES_PER_SECOND;
+            }
+        }
+
+        self.rtc_dirty |= self.rtc_clock.get_mode() == RtcMode::EMULATED;
+    }
+
+    fn clock_minutes(&self) -> u64 {
+        (self.days as u64 * HUC3_MINUTES_PER_DAY as u64) + self.minutes as u64
+    }
+
+    fn read_nibbles(&self, address: usize, count: usize) -> u16 {
+        (0..count).fold(0, |value, i| value | ((self.memory[address + i] as u16 & 0x0f) << (i * 4)))
+    }
+
+    fn write_nibbles(&mut self, address: usize, count: usize, value: u16) {
+        for i in 0..count {
+            self.memory[address + i] = ((value >> (i * 4)) & 0x0f) as u8;
+        }
+    }
+
+    fn alarm(&self) -> (u16, u16) {
+        (self.read_nibbles(HUC3_ALARM_ADDRESS, 3), self.read_nibbles(HUC3_ALARM_ADDRESS + 3, 3))
+    }
+
+    fn alarm_enabled(&self) -> bool {
+        (self.memory[HUC3_ALARM_ENABLE_ADDRESS] & 0x01) != 0
+    }
+
+    fn command(&mut self, value: u8) {
+        let argument = value & 0x0f;
+
+        match value >> 4 {
+            0x1 => {
+                self.response = self.memory[self.address as usize] & 0x0f;
+                self.address = self.address.wrapping_add(1);
+            },
+            0x3 => {
+                self.memory[self.address as usize] = argument;
+                self.address = self.address.wrapping_add(1);
+                self.rtc_dirty = true;
+            },
+            0x4 => self.address = (self.address & 0xf0) | argument,
+            0x5 => self.address = (self.address & 0x0f) | (argument << 4),
+            0x6 => match argument {
+                0x0 => {
+                    self.update_rtc();
+
+                    let (minutes, days) = (self.minutes, self.days);
+                    self.write_nibbles(0x00, 3, minutes);
+                    self.write_nibbles(0x03, 3, days);
+                },
+                0x1 => {
+                    self.update_rtc();
+
+                    self.minutes = self.read_nibbles(0x00, 3) % HUC3_MINUTES_PER_DAY as u16;
+                    self.days = self.read_nibbles(0x03, 3);
+                    self.seconds = 0;
+                    self.rtc_clock.reset_subsecond();
+                    self.rtc_dirty = true;
+                },
+                0x2 => self.response = 0x01,
+                0xe => self.tone_cycles = RTC_CYCLES_PER_SECOND,
+                _ => println!("WARN: unknown HuC3 extended command {:#x}", argument),
+            },
+            _ => println!("WARN: unknown HuC3 command {:#04x}", value),
+        }
+    }
+}
+
+impl Mapper for MapperHuC3 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank as usize
+        } else {
+            0
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        match self.mode {
+            HuC3Mode::RAMREAD | HuC3Mode::RAMWRITE if self.cartridge.has_ram() => {
+                let ram_size = self.cartridge.get_ram_length() - 1;
+                let bank_address = (self.ram_bank as usize * 0x2000) | (address as usize & 0x1fff);
+                self.cartridge.read_ram(bank_address & ram_size)
+            },
+            HuC3Mode::RESPONSE => 0x80 | self.response,
+            HuC3Mode::SEMAPHORE => 0xff,
+            HuC3Mode::IR => 0xc0 | self.ir_input as u8,
+            _ => 0xff,
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1fff => {
+                self.mode = match value & 0x0f {
+                    0x0a => HuC3Mode::RAMWRITE,
+                    0x0b => HuC3Mode::COMMAND,
+                    0x0c => HuC3Mode::RESPONSE,
+                    0x0d => HuC3Mode::SEMAPHORE,
+                    0x0e => HuC3Mode::IR,
+                    _ => HuC3Mode::RAMREAD,
+                };
+            },
+            0x2000..=0x3fff => {
+                self.rom_bank = value & 0x7f;
+
+                if self.rom_bank == 0 {
+                    self.rom_bank = 0x01;
+                }
+            },
+            0x4000..=0x5fff => {
+                self.ram_bank = value & 0x03;
+            },
+            0x6000..=0x7fff => {},
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        match self.mode {
+            HuC3Mode::RAMWRITE if self.cartridge.has_ram() => {
+                let ram_size = self.cartridge.get_ram_length() - 1;
+                let bank_address = (self.ram_bank as usize * 0x2000) | (address as usize & 0x1fff);
+                self.cartridge.write_ram(bank_address & ram_size, value);
+            },
+            HuC3Mode::COMMAND => self.command(value),
+            HuC3Mode::IR => self.ir_led = (value & 0x01) != 0,
+            _ => {},
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+
+    fn has_battery(&self) -> bool {
+        self.cartridge.get_type().has_battery()
+    }
+
+    fn battery_dirty(&self) -> bool {
+        self.cartridge.is_ram_dirty() || self.rtc_dirty
+    }
+
+    fn load_battery(&mut self, data: &[u8]) {
+        let ram_size = self.cartridge.get_ram_length();
+        let (ram, footer) = data.split_at(ram_size.min(data.len()));
+
+        self.cartridge.load_ram(ram);
+
+        if footer.len() >= HUC3_RTC_SAVE_SIZE {
+            let mut timestamp = [0u8; 8];
+            timestamp.copy_from_slice(&footer[0..8]);
+
+            let word = |i: usize| u16::from_le_bytes([footer[i], footer[i + 1]]);
+
+            self.minutes = word(8) % HUC3_MINUTES_PER_DAY as u16;
+            self.days = word(10) & 0xfff;
+            self.write_nibbles(HUC3_ALARM_ADDRESS, 3, word(12));
+            self.write_nibbles(HUC3_ALARM_ADDRESS + 3, 3, word(14));
+            self.memory[HUC3_ALARM_ENABLE_ADDRESS] = footer[16] & 0x01;
+
+            self.rtc_clock.load_timestamp(u64::from_le_bytes(timestamp));
+            self.update_rtc();
+        }
+
+        self.rtc_dirty = false;
+    }
+
+    fn save_battery(&mut self) -> Vec<u8> {
+        self.update_rtc();
+
+        let (alarm_minutes, alarm_days) = self.alarm();
+        let mut data = self.cartridge.save_ram();
+
+        data.extend_from_slice(&self.rtc_clock.timestamp().to_le_bytes());
+        data.extend_from_slice(&self.minutes.to_le_bytes());
+        data.extend_from_slice(&self.days.to_le_bytes());
+        data.extend_from_slice(&alarm_minutes.to_le_bytes());
+        data.extend_from_slice(&alarm_days.to_le_bytes());
+        data.push(self.alarm_enabled() as u8);
+
+        self.rtc_dirty = false;
+        data
+    }
+
+    fn tick(&mut self, cycles: usize) {
+        self.tone_cycles = self.tone_cycles.saturating_sub(cycles);
+
+        if self.rtc_clock.tick(cycles) {
+            self.update_rtc();
+        }
+    }
+
+    fn set_rtc_mode(&mut self, mode: RtcMode) {
+        self.rtc_clock.set_mode(mode);
+    }
+
+    fn tone(&self) -> bool {
+        self.tone_cycles > 0
+    }
+
+    fn ir_led(&self) -> bool {
+        self.ir_led
+    }
+
+    fn set_ir_input(&mut self, light: bool) {
+        self.ir_input = light;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapper() -> MapperHuC3 {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0xfe;
+        rom[0x149] = 0x03;
+
+        let mut mapper = MapperHuC3::new(Cartridge::from_bytes(rom).unwrap());
+        mapper.set_rtc_mode(RtcMode::EMULATED);
+        mapper
+    }
+
+    fn command(mapper: &mut MapperHuC3, value: u8) {
+        mapper.write_rom(0x0000, 0x0b);
+        mapper.write_ram(0xa000, value);
+    }
+
+    fn set_address(mapper: &mut MapperHuC3, address: u8) {
+        command(mapper, 0x40 | (address & 0x0f));
+        command(mapper, 0x50 | (address >> 4));
+    }
+
+    fn write_nibbles(mapper: &mut MapperHuC3, address: u8, value: u16, count: usize) {
+        set_address(mapper, address);
+
+        for i in 0..count {
+            command(mapper, 0x30 | ((value >> (i * 4)) & 0x0f) as u8);
+        }
+    }
+
+    fn read_nibbles(mapper: &mut MapperHuC3, address: u8, count: usize) -> u16 {
+        set_address(mapper, address);
+
+        (0..count).fold(0, |value, i| {
+            command(mapper, 0x10);
+            mapper.write_rom(0x0000, 0x0c);
+            value | ((mapper.read_ram(0xa000) as u16 & 0x0f) << (i * 4))
+        })
+    }
+
+    fn set_clock(mapper: &mut MapperHuC3, minutes: u16, days: u16) {
+        write_nibbles(mapper, 0x00, minutes, 3);
+        write_nibbles(mapper, 0x03, days, 3);
+        command(mapper, 0x61);
+    }
+
+    #[test]
+    fn clock_commands_write_and_read_the_time() {
+        let mut mapper = mapper();
+
+        set_clock(&mut mapper, 1439, 0x123);
+        mapper.tick(RTC_CYCLES_PER_SECOND * 90);
+        command(&mut mapper, 0x60);
+
+        assert_eq!(read_nibbles(&mut mapper, 0x00, 3), 0);
+        assert_eq!(read_nibbles(&mut mapper, 0x03, 3), 0x124);
+    }
+
+    #[test]
+    fn alarm_rings_when_a_jump_passes_it() {
+        let mut mapper = mapper();
+
+        set_clock(&mut mapper, 100, 2);
+        write_nibbles(&mut mapper, 0x10, 105, 3);
+        write_nibbles(&mut mapper, 0x13, 2, 3);
+        write_nibbles(&mut mapper, 0x16, 0x1, 1);
+
+        mapper.tick(RTC_CYCLES_PER_SECOND * 60 * 4);
+        assert!(!mapper.tone());
+
+        mapper.tick(RTC_CYCLES_PER_SECOND * 60 * 10);
+        assert!(mapper.tone());
+    }
+
+    #[test]
+    fn alarm_does_not_ring_when_disabled() {
+        let mut mapper = mapper();
+
+        set_clock(&mut mapper, 100, 2);
+        write_nibbles(&mut mapper, 0x10, 105, 3);
+        write_nibbles(&mut mapper, 0x13, 2, 3);
+
+        mapper.tick(RTC_CYCLES_PER_SECOND * 60 * 10);
+        assert!(!mapper.tone());
+    }
+}
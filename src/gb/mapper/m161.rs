@@ -0,0 +1,81 @@
+use super::super::cartridge::{Cartridge, M161_GAME_SIZE};
+use super::Mapper;
+
+pub struct MapperM161 {
+    cartridge: Cartridge,
+    rom_bank: u8,
+    locked: bool,
+}
+
+impl MapperM161 {
+    pub fn new(cartridge: Cartridge) -> MapperM161 {
+        MapperM161 {
+            cartridge,
+            rom_bank: 0,
+            locked: false,
+        }
+    }
+}
+
+impl Mapper for MapperM161 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (self.rom_bank as usize * M161_GAME_SIZE) | (address as usize & 0x7fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, _address: u16) -> u8 {
+        0xff
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        if let 0x4000..=0x5fff = address {
+            if !self.locked {
+                self.rom_bank = value & 0x07;
+                self.locked = true;
+            }
+        }
+    }
+
+    fn write_ram(&mut self, _address: u16, _value: u8) {}
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::cartridge::CartridgeMapper;
+
+    #[test]
+    fn first_bank_write_locks_the_game() {
+        let mut rom = vec![0; M161_GAME_SIZE * 8];
+
+        for game in 0..8 {
+            rom[(game * M161_GAME_SIZE) + 0x0200] = game as u8;
+            rom[(game * M161_GAME_SIZE) + 0x4200] = 0x10 | game as u8;
+        }
+
+        let mut cartridge = Cartridge::from_bytes(rom).unwrap();
+        cartridge.set_mapper(CartridgeMapper::M161);
+
+        let mut mapper = MapperM161::new(cartridge);
+        assert_eq!(mapper.read_rom(0x0200), 0x00);
+
+        mapper.write_rom(0x2000, 0x05);
+        assert_eq!(mapper.read_rom(0x0200), 0x00);
+
+        mapper.write_rom(0x4000, 0x0b);
+        assert_eq!(mapper.read_rom(0x0200), 0x03);
+        assert_eq!(mapper.read_rom(0x4200), 0x13);
+
+        mapper.write_rom(0x4000, 0x05);
+        assert_eq!(mapper.read_rom(0x0200), 0x03);
+    }
+}
@@ -0,0 +1,136 @@
+use super::super::cartridge::Cartridge;
+use super::Mapper;
+
+#[derive(PartialEq)]
+enum MBC1BankingMode {
+    ROM,
+    RAM,
+}
+
+pub struct MapperMBC1 {
+    cartridge: Cartridge,
+    ram_enable: bool,
+    rom_bank: u8,
+    ram_bank: u8,
+    banking_mode: MBC1BankingMode,
+    multicart: bool,
+}
+
+impl MapperMBC1 {
+    pub fn new(cartridge: Cartridge, multicart: bool) -> MapperMBC1 {
+        MapperMBC1 {
+            cartridge,
+            ram_enable: false,
+            rom_bank: 1,
+            ram_bank: 0,
+            banking_mode: MBC1BankingMode::ROM,
+            multicart,
+        }
+    }
+
+    fn rom_bank_shift(&self) -> u8 {
+        if self.multicart {
+            4
+        } else {
+            5
+        }
+    }
+
+    fn rom_bank_lower(&self) -> u8 {
+        if self.banking_mode == MBC1BankingMode::RAM {
+            (self.ram_bank & 0x03) << self.rom_bank_shift()
+        } else {
+            0
+        }
+    }
+
+    fn rom_bank_upper(&self) -> u8 {
+        let mask = (1 << self.rom_bank_shift()) - 1;
+
+        (self.rom_bank & mask) | ((self.ram_bank & 0x03) << self.rom_bank_shift())
+    }
+
+    fn ram_bank(&self) -> u8 {
+        let mut ram_bank = 0;
+
+        if self.banking_mode == MBC1BankingMode::RAM {
+            ram_bank = self.ram_bank & 0x03;
+        }
+
+        ram_bank
+    }
+}
+
+impl Mapper for MapperMBC1 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank_upper() as usize
+        } else {
+            self.rom_bank_lower() as usize
+        };
+        
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = self.ram_bank() as usize;
+
+        let ram_size = self.cartridge.get_ram_length().wrapping_sub(1);
+        let bank_address = (bank * 0x2000) | (address & 0x1fff);
+
+        if self.ram_enable && self.cartridge.has_ram() {
+            self.cartridge.read_ram(bank_address & ram_size)
+        } else {
+            0xff
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1fff => {
+                self.ram_enable = (value & 0x0a) == 0x0a;
+            },
+            0x2000..=0x3fff => {
+                self.rom_bank = value & 0x1f;
+
+                if self.rom_bank & 0x1f == 0 {
+                    self.rom_bank = 0x01;
+                }
+            },
+            0x4000..=0x5fff => {
+                self.ram_bank = value & 0x03;
+            },
+            0x6000..=0x7fff => {
+                self.banking_mode = match (value & 0x01) != 0 {
+                    true => MBC1BankingMode::RAM,
+                    false => MBC1BankingMode::ROM,
+                };
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        let address = address as usize;
+        let bank = self.ram_bank() as usize;
+
+        let ram_size = self.cartridge.get_ram_length().wrapping_sub(1);
+        let bank_address = (bank * 0x2000) | (address & 0x1fff);
+
+        if self.ram_enable && self.cartridge.has_ram() {
+            self.cartridge.write_ram(bank_address & ram_size, value);
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+}
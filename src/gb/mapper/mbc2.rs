@@ -0,0 +1,121 @@
+use super::super::cartridge::Cartridge;
+use super::Mapper;
+
+pub struct MapperMBC2 {
+    cartridge: Cartridge,
+    ram_enable: bool,
+    rom_bank: u8,
+}
+
+impl MapperMBC2 {
+    pub fn new(cartridge: Cartridge) -> MapperMBC2 {
+        MapperMBC2 {
+            cartridge,
+            ram_enable: false,
+            rom_bank: 1,
+        }
+    }
+}
+
+impl Mapper for MapperMBC2 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank as usize
+        } else {
+            0
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        if self.ram_enable {
+            self.cartridge.read_ram(address as usize & 0x01ff) | 0xf0
+        } else {
+            0xff
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x3fff => {
+                if address & 0x0100 == 0 {
+                    self.ram_enable = (value & 0x0f) == 0x0a;
+                } else {
+                    self.rom_bank = value & 0x0f;
+
+                    if self.rom_bank == 0 {
+                        self.rom_bank = 0x01;
+                    }
+                }
+            },
+            0x4000..=0x7fff => {},
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.ram_enable {
+            self.cartridge.write_ram(address as usize & 0x01ff, value & 0x0f);
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapper() -> MapperMBC2 {
+        let mut rom = vec![0; 0x40000];
+        rom[0x147] = 0x06;
+        rom[0x148] = 0x03;
+
+        for bank in 0..16 {
+            rom[bank * 0x4000 + 0x10] = bank as u8;
+        }
+
+        MapperMBC2::new(Cartridge::from_bytes(rom).unwrap())
+    }
+
+    #[test]
+    fn address_bit_8_selects_register() {
+        let mut mapper = mapper();
+
+        mapper.write_rom(0x2000, 0x03);
+        assert_eq!(mapper.read_rom(0x4010), 0x01);
+        assert_eq!(mapper.read_ram(0xa000), 0xff);
+
+        mapper.write_rom(0x2100, 0x03);
+        assert_eq!(mapper.read_rom(0x4010), 0x03);
+
+        mapper.write_rom(0x0100, 0x00);
+        assert_eq!(mapper.read_rom(0x4010), 0x01);
+
+        mapper.write_rom(0x0000, 0x0a);
+        mapper.write_ram(0xa000, 0x05);
+        assert_eq!(mapper.read_ram(0xa000), 0xf5);
+    }
+
+    #[test]
+    fn ram_is_four_bits_and_echoes() {
+        let mut mapper = mapper();
+
+        mapper.write_rom(0x0000, 0x0a);
+        mapper.write_ram(0xa010, 0x5c);
+
+        assert_eq!(mapper.read_ram(0xa010), 0xfc);
+        assert_eq!(mapper.read_ram(0xa210), 0xfc);
+        assert_eq!(mapper.read_ram(0xbe10), 0xfc);
+    }
+}
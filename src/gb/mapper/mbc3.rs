@@ -0,0 +1,381 @@
+use super::super::cartridge::Cartridge;
+use super::super::rtc::{RtcClock, RtcMode};
+use super::Mapper;
+
+pub const MBC3_RTC_SAVE_SIZE: usize = 48;
+
+#[derive(Clone, Copy, Default)]
+struct MBC3Rtc {
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+    days: u16,
+    halt: bool,
+    carry: bool,
+}
+
+impl MBC3Rtc {
+    fn is_valid(&self) -> bool {
+        self.seconds < 60 && self.minutes < 60 && self.hours < 24
+    }
+
+    fn step(&mut self) {
+        self.seconds = (self.seconds + 1) & 0x3f;
+
+        if self.seconds == 60 {
+            self.seconds = 0;
+            self.minutes = (self.minutes + 1) & 0x3f;
+
+            if self.minutes == 60 {
+                self.minutes = 0;
+                self.hours = (self.hours + 1) & 0x1f;
+
+                if self.hours == 24 {
+                    self.hours = 0;
+                    self.advance_days(1);
+                }
+            }
+        }
+    }
+
+    fn advance_days(&mut self, days: u64) {
+        let days = self.days as u64 + days;
+
+        if days > 0x1ff {
+            self.carry = true;
+        }
+
+        self.days = (days & 0x1ff) as u16;
+    }
+
+    fn advance(&mut self, mut seconds: u64) {
+        if self.halt {
+            return;
+        }
+
+        while seconds > 0 && !self.is_valid() {
+            self.step();
+            seconds -= 1;
+        }
+
+        if seconds == 0 {
+            return;
+        }
+
+        let total = self.seconds as u64 + (self.minutes as u64 * 60) + (self.hours as u64 * 3600) + seconds;
+
+        self.seconds = (total % 60) as u8;
+        self.minutes = ((total / 60) % 60) as u8;
+        self.hours = ((total / 3600) % 24) as u8;
+        self.advance_days(total / 86400);
+    }
+
+    fn read(&self, register: u8) -> u8 {
+        match register {
+            0x08 => self.seconds,
+            0x09 => self.minutes,
+            0x0a => self.hours,
+            0x0b => self.days as u8,
+            0x0c => ((self.days >> 8) as u8 & 0x01) | ((self.halt as u8) << 6) | ((self.carry as u8) << 7),
+            _ => 0xff,
+        }
+    }
+
+    fn write(&mut self, register: u8, value: u8) {
+        match register {
+            0x08 => self.seconds = value & 0x3f,
+            0x09 => self.minutes = value & 0x3f,
+            0x0a => self.hours = value & 0x1f,
+            0x0b => self.days = (self.days & 0x100) | value as u16,
+            0x0c => {
+                self.days = (self.days & 0xff) | ((value as u16 & 0x01) << 8);
+                self.halt = (value & 0x40) != 0;
+                self.carry = (value & 0x80) != 0;
+            },
+            _ => {},
+        }
+    }
+
+    fn save(&self, data: &mut Vec<u8>) {
+        for register in 0x08..=0x0c {
+            data.extend_from_slice(&(self.read(register) as u32).to_le_bytes());
+        }
+    }
+
+    fn load(&mut self, data: &[u8]) {
+        for (i, register) in (0x08..=0x0c).enumerate() {
+            self.write(register, data[i * 4]);
+        }
+    }
+}
+
+pub struct MapperMBC3 {
+    cartridge: Cartridge,
+    ram_enable: bool,
+    rom_bank: u8,
+    ram_bank: u8,
+    latch: u8,
+    rtc: MBC3Rtc,
+    rtc_latched: MBC3Rtc,
+    rtc_clock: RtcClock,
+    rtc_dirty: bool,
+}
+
+impl MapperMBC3 {
+    pub fn new(cartridge: Cartridge) -> MapperMBC3 {
+        MapperMBC3 {
+            cartridge,
+            ram_enable: false,
+            rom_bank: 1,
+            ram_bank: 0,
+            latch: 0xff,
+            rtc: MBC3Rtc::default(),
+            rtc_latched: MBC3Rtc::default(),
+            rtc_clock: RtcClock::new(),
+            rtc_dirty: false,
+        }
+    }
+
+    fn has_timer(&self) -> bool {
+        self.cartridge.get_type().has_timer()
+    }
+
+    fn update_rtc(&mut self) {
+        let elapsed = self.rtc_clock.elapsed();
+
+        if elapsed > 0 && !self.rtc.halt {
+            self.rtc.advance(elapsed);
+            self.rtc_dirty |= self.rtc_clock.get_mode() == RtcMode::EMULATED;
+        }
+    }
+}
+
+impl Mapper for MapperMBC3 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank as usize
+        } else {
+            0
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        if !self.ram_enable {
+            return 0xff;
+        }
+
+        match self.ram_bank {
+            0x00..=0x03 if self.cartridge.has_ram() => {
+                let ram_size = self.cartridge.get_ram_length() - 1;
+                let bank_address = (self.ram_bank as usize * 0x2000) | (address as usize & 0x1fff);
+                self.cartridge.read_ram(bank_address & ram_size)
+            },
+            0x08..=0x0c if self.has_timer() => self.rtc_latched.read(self.ram_bank),
+            _ => 0xff,
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1fff => {
+                self.ram_enable = (value & 0x0f) == 0x0a;
+            },
+            0x2000..=0x3fff => {
+                self.rom_bank = value & 0x7f;
+
+                if self.rom_bank == 0 {
+                    self.rom_bank = 0x01;
+                }
+            },
+            0x4000..=0x5fff => {
+                self.ram_bank = value & 0x0f;
+            },
+            0x6000..=0x7fff => {
+                if self.latch == 0x00 && value == 0x01 && self.has_timer() {
+                    self.update_rtc();
+                    self.rtc_latched = self.rtc;
+                }
+
+                self.latch = value;
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if !self.ram_enable {
+            return;
+        }
+
+        match self.ram_bank {
+            0x00..=0x03 if self.cartridge.has_ram() => {
+                let ram_size = self.cartridge.get_ram_length() - 1;
+                let bank_address = (self.ram_bank as usize * 0x2000) | (address as usize & 0x1fff);
+                self.cartridge.write_ram(bank_address & ram_size, value);
+            },
+            0x08..=0x0c if self.has_timer() => {
+                self.update_rtc();
+
+                if self.ram_bank == 0x08 {
+                    self.rtc_clock.reset_subsecond();
+                }
+
+                self.rtc.write(self.ram_bank, value);
+                self.rtc_dirty = true;
+            },
+            _ => {},
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+
+    fn has_battery(&self) -> bool {
+        self.cartridge.get_type().has_battery() && (self.cartridge.has_ram() || self.has_timer())
+    }
+
+    fn battery_dirty(&self) -> bool {
+        self.cartridge.is_ram_dirty() || self.rtc_dirty
+    }
+
+    fn load_battery(&mut self, data: &[u8]) {
+        let ram_size = self.cartridge.get_ram_length();
+        let (ram, footer) = data.split_at(ram_size.min(data.len()));
+
+        self.cartridge.load_ram(ram);
+
+        if self.has_timer() && footer.len() >= MBC3_RTC_SAVE_SIZE - 4 {
+            self.rtc.load(&footer[0..20]);
+            self.rtc_latched.load(&footer[20..40]);
+
+            let mut timestamp = [0u8; 8];
+            let length = (footer.len() - 40).min(8);
+            timestamp[..length].copy_from_slice(&footer[40..40 + length]);
+
+            self.rtc_clock.load_timestamp(u64::from_le_bytes(timestamp));
+            self.update_rtc();
+        }
+
+        self.rtc_dirty = false;
+    }
+
+    fn save_battery(&mut self) -> Vec<u8> {
+        let mut data = self.cartridge.save_ram();
+
+        if self.has_timer() {
+            self.update_rtc();
+
+            self.rtc.save(&mut data);
+            self.rtc_latched.save(&mut data);
+            data.extend_from_slice(&self.rtc_clock.timestamp().to_le_bytes());
+        }
+
+        self.rtc_dirty = false;
+        data
+    }
+
+    fn tick(&mut self, cycles: usize) {
+        if self.rtc_clock.tick(cycles) && self.has_timer() {
+            self.update_rtc();
+        }
+    }
+
+    fn set_rtc_mode(&mut self, mode: RtcMode) {
+        self.rtc_clock.set_mode(mode);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::rtc::RTC_CYCLES_PER_SECOND;
+    use super::*;
+
+    fn mapper() -> MapperMBC3 {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0x10;
+        rom[0x149] = 0x03;
+
+        let mut mapper = MapperMBC3::new(Cartridge::from_bytes(rom).unwrap());
+        mapper.set_rtc_mode(RtcMode::EMULATED);
+        mapper.write_rom(0x0000, 0x0a);
+        mapper
+    }
+
+    fn write_rtc(mapper: &mut MapperMBC3, register: u8, value: u8) {
+        mapper.write_rom(0x4000, register);
+        mapper.write_ram(0xa000, value);
+    }
+
+    fn read_rtc(mapper: &mut MapperMBC3, register: u8) -> u8 {
+        mapper.write_rom(0x4000, register);
+        mapper.read_ram(0xa000)
+    }
+
+    fn latch(mapper: &mut MapperMBC3) {
+        mapper.write_rom(0x6000, 0x00);
+        mapper.write_rom(0x6000, 0x01);
+    }
+
+    #[test]
+    fn latch_needs_zero_then_one() {
+        let mut mapper = mapper();
+
+        write_rtc(&mut mapper, 0x08, 5);
+        assert_eq!(read_rtc(&mut mapper, 0x08), 0);
+
+        latch(&mut mapper);
+        assert_eq!(read_rtc(&mut mapper, 0x08), 5);
+
+        write_rtc(&mut mapper, 0x08, 9);
+        mapper.write_rom(0x6000, 0x01);
+        assert_eq!(read_rtc(&mut mapper, 0x08), 5);
+
+        latch(&mut mapper);
+        assert_eq!(read_rtc(&mut mapper, 0x08), 9);
+    }
+
+    #[test]
+    fn halt_stops_the_clock() {
+        let mut mapper = mapper();
+
+        write_rtc(&mut mapper, 0x0c, 0x40);
+        mapper.tick(RTC_CYCLES_PER_SECOND * 3);
+        latch(&mut mapper);
+        assert_eq!(read_rtc(&mut mapper, 0x08), 0);
+        assert_eq!(read_rtc(&mut mapper, 0x0c), 0x40);
+
+        write_rtc(&mut mapper, 0x0c, 0x00);
+        mapper.tick(RTC_CYCLES_PER_SECOND * 2);
+        latch(&mut mapper);
+        assert_eq!(read_rtc(&mut mapper, 0x08), 2);
+    }
+
+    #[test]
+    fn day_counter_overflow_sets_carry() {
+        let mut mapper = mapper();
+
+        write_rtc(&mut mapper, 0x08, 59);
+        write_rtc(&mut mapper, 0x09, 59);
+        write_rtc(&mut mapper, 0x0a, 23);
+        write_rtc(&mut mapper, 0x0b, 0xff);
+        write_rtc(&mut mapper, 0x0c, 0x01);
+
+        mapper.tick(RTC_CYCLES_PER_SECOND);
+        latch(&mut mapper);
+
+        assert_eq!(read_rtc(&mut mapper, 0x08), 0);
+        assert_eq!(read_rtc(&mut mapper, 0x0a), 0);
+        assert_eq!(read_rtc(&mut mapper, 0x0b), 0x00);
+        assert_eq!(read_rtc(&mut mapper, 0x0c), 0x80);
+    }
+}
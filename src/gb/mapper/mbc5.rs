@@ -0,0 +1,165 @@
+use super::super::cartridge::Cartridge;
+use super::Mapper;
+
+pub struct MapperMBC5 {
+    cartridge: Cartridge,
+    ram_enable: bool,
+    rom_bank: u16,
+    ram_bank: u8,
+    rumble: bool,
+}
+
+impl MapperMBC5 {
+    pub fn new(cartridge: Cartridge) -> MapperMBC5 {
+        MapperMBC5 {
+            cartridge,
+            ram_enable: false,
+            rom_bank: 1,
+            ram_bank: 0,
+            rumble: false,
+        }
+    }
+}
+
+impl Mapper for MapperMBC5 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank as usize
+        } else {
+            0
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = self.ram_bank as usize;
+
+        let ram_size = self.cartridge.get_ram_length().wrapping_sub(1);
+        let bank_address = (bank * 0x2000) | (address & 0x1fff);
+
+        if self.ram_enable && self.cartridge.has_ram() {
+            self.cartridge.read_ram(bank_address & ram_size)
+        } else {
+            0xff
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1fff => {
+                self.ram_enable = value == 0x0a;
+            },
+            0x2000..=0x2fff => {
+                self.rom_bank = (self.rom_bank & 0x100) | value as u16;
+            },
+            0x3000..=0x3fff => {
+                self.rom_bank = (self.rom_bank & 0xff) | ((value as u16 & 0x01) << 8);
+            },
+            0x4000..=0x5fff => {
+                if self.cartridge.get_type().has_rumble() {
+                    self.ram_bank = value & 0x07;
+                    self.rumble = (value & 0x08) != 0;
+                } else {
+                    self.ram_bank = value & 0x0f;
+                }
+            },
+            0x6000..=0x7fff => {},
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        let address = address as usize;
+        let bank = self.ram_bank as usize;
+
+        let ram_size = self.cartridge.get_ram_length().wrapping_sub(1);
+        let bank_address = (bank * 0x2000) | (address & 0x1fff);
+
+        if self.ram_enable && self.cartridge.has_ram() {
+            self.cartridge.write_ram(bank_address & ram_size, value);
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+
+    fn rumble(&self) -> bool {
+        self.rumble
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapper(cartridge_type: u8) -> MapperMBC5 {
+        let mut rom = vec![0; 0x800000];
+        rom[0x147] = cartridge_type;
+        rom[0x148] = 0x08;
+        rom[0x149] = 0x04;
+
+        for bank in 0..0x200 {
+            rom[bank * 0x4000 + 0x10] = bank as u8;
+            rom[bank * 0x4000 + 0x11] = (bank >> 8) as u8;
+        }
+
+        MapperMBC5::new(Cartridge::from_bytes(rom).unwrap())
+    }
+
+    #[test]
+    fn rom_bank_has_nine_bits() {
+        let mut mapper = mapper(0x19);
+
+        mapper.write_rom(0x2000, 0x23);
+        assert_eq!((mapper.read_rom(0x4010), mapper.read_rom(0x4011)), (0x23, 0x00));
+
+        mapper.write_rom(0x3000, 0x01);
+        assert_eq!((mapper.read_rom(0x4010), mapper.read_rom(0x4011)), (0x23, 0x01));
+
+        mapper.write_rom(0x2000, 0x00);
+        assert_eq!((mapper.read_rom(0x4010), mapper.read_rom(0x4011)), (0x00, 0x01));
+
+        mapper.write_rom(0x3000, 0x00);
+        assert_eq!((mapper.read_rom(0x4010), mapper.read_rom(0x4011)), (0x00, 0x00));
+    }
+
+    #[test]
+    fn rumble_bit_is_taken_from_the_ram_bank() {
+        let mut mapper = mapper(0x1e);
+
+        mapper.write_rom(0x0000, 0x0a);
+        mapper.write_rom(0x4000, 0x0b);
+        assert!(mapper.rumble());
+
+        mapper.write_ram(0xa000, 0x33);
+        mapper.write_rom(0x4000, 0x03);
+        assert!(!mapper.rumble());
+        assert_eq!(mapper.read_ram(0xa000), 0x33);
+
+        mapper.write_rom(0x4000, 0x0b);
+        assert_eq!(mapper.read_ram(0xa000), 0x33);
+    }
+
+    #[test]
+    fn rumble_bit_is_a_ram_bank_without_a_motor() {
+        let mut mapper = mapper(0x1b);
+
+        mapper.write_rom(0x0000, 0x0a);
+        mapper.write_rom(0x4000, 0x03);
+        mapper.write_ram(0xa000, 0x33);
+
+        mapper.write_rom(0x4000, 0x0b);
+        assert!(!mapper.rumble());
+        assert_eq!(mapper.read_ram(0xa000), 0x00);
+    }
+}
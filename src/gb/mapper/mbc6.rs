@@ -0,0 +1,307 @@
+use super::super::cartridge::Cartridge;
+use super::Mapper;
+
+pub const MBC6_FLASH_SIZE: usize = 0x100000;
+pub const MBC6_FLASH_SECTOR_SIZE: usize = 0x20000;
+
+const FLASH_MANUFACTURER_ID: u8 = 0xc2;
+const FLASH_DEVICE_ID: u8 = 0x81;
+
+#[derive(Clone, Copy, PartialEq)]
+enum FlashState {
+    READ,
+    UNLOCK1,
+    UNLOCK2,
+    PROGRAM,
+    ERASE,
+    ERASEUNLOCK1,
+    ERASEUNLOCK2,
+}
+
+pub struct MapperMBC6 {
+    cartridge: Cartridge,
+    ram_enable: bool,
+    ram_bank: [u8; 2],
+    rom_bank: [u8; 2],
+    flash_select: [bool; 2],
+    flash_enable: bool,
+    flash_write_enable: bool,
+    flash: Box<[u8]>,
+    flash_dirty: bool,
+    flash_state: FlashState,
+    flash_id: bool,
+}
+
+impl MapperMBC6 {
+    pub fn new(cartridge: Cartridge) -> MapperMBC6 {
+        MapperMBC6 {
+            cartridge,
+            ram_enable: false,
+            ram_bank: [0, 0],
+            rom_bank: [0, 0],
+            flash_select: [false, false],
+            flash_enable: false,
+            flash_write_enable: false,
+            flash: vec![0xff; MBC6_FLASH_SIZE].into_boxed_slice(),
+            flash_dirty: false,
+            flash_state: FlashState::READ,
+            flash_id: false,
+        }
+    }
+
+    fn window(address: u16) -> usize {
+        ((address as usize) >> 13) & 0x01
+    }
+
+    fn flash_address(&self, address: u16) -> usize {
+        let bank = self.rom_bank[MapperMBC6::window(address)] as usize;
+        ((bank * 0x2000) | (address as usize & 0x1fff)) % MBC6_FLASH_SIZE
+    }
+
+    fn ram_address(&self, address: u16) -> usize {
+        let bank = self.ram_bank[((address as usize) >> 12) & 0x01] as usize;
+        let ram_size = self.cartridge.get_ram_length() - 1;
+
+        ((bank * 0x1000) | (address as usize & 0x0fff)) & ram_size
+    }
+
+    fn erase(&mut self, start: usize, length: usize) {
+        if self.flash_write_enable {
+            for byte in self.flash[start..start + length].iter_mut() {
+                *byte = 0xff;
+            }
+
+            self.flash_dirty = true;
+        }
+    }
+
+    fn write_flash(&mut self, address: u16, value: u8) {
+        if !self.flash_enable {
+            return;
+        }
+
+        let flash_address = self.flash_address(address);
+        let command_address = flash_address & 0x7fff;
+
+        if value == 0xf0 && self.flash_state != FlashState::PROGRAM {
+            self.flash_state = FlashState::READ;
+            self.flash_id = false;
+            return;
+        }
+
+        self.flash_state = match (self.flash_state, command_address, value) {
+            (FlashState::READ, 0x5555, 0xaa) => FlashState::UNLOCK1,
+            (FlashState::UNLOCK1, 0x2aaa, 0x55) => FlashState::UNLOCK2,
+            (FlashState::UNLOCK2, 0x5555, 0x90) => {
+                self.flash_id = true;
+                FlashState::READ
+            },
+            (FlashState::UNLOCK2, 0x5555, 0xa0) => FlashState::PROGRAM,
+            (FlashState::UNLOCK2, 0x5555, 0x80) => FlashState::ERASE,
+            (FlashState::PROGRAM, _, _) => {
+                if self.flash_write_enable {
+                    self.flash[flash_address] &= value;
+                    self.flash_dirty = true;
+                }
+
+                FlashState::READ
+            },
+            (FlashState::ERASE, 0x5555, 0xaa) => FlashState::ERASEUNLOCK1,
+            (FlashState::ERASEUNLOCK1, 0x2aaa, 0x55) => FlashState::ERASEUNLOCK2,
+            (FlashState::ERASEUNLOCK2, _, 0x30) => {
+                let sector = flash_address & !(MBC6_FLASH_SECTOR_SIZE - 1);
+                self.erase(sector, MBC6_FLASH_SECTOR_SIZE);
+                FlashState::READ
+            },
+            (FlashState::ERASEUNLOCK2, 0x5555, 0x10) => {
+                self.erase(0, MBC6_FLASH_SIZE);
+                FlashState::READ
+            },
+            _ => FlashState::READ,
+        };
+    }
+}
+
+impl Mapper for MapperMBC6 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        if address < 0x4000 {
+            return self.cartridge.read_rom(address as usize);
+        }
+
+        let window = MapperMBC6::window(address);
+
+        if self.flash_select[window] {
+            if self.flash_id {
+                return match address & 0x01 {
+                    0x00 => FLASH_MANUFACTURER_ID,
+                    _ => FLASH_DEVICE_ID,
+                };
+            }
+
+            return self.flash[self.flash_address(address)];
+        }
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (self.rom_bank[window] as usize * 0x2000) | (address as usize & 0x1fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        if self.ram_enable && self.cartridge.has_ram() {
+            let ram_address = self.ram_address(address);
+            self.cartridge.read_ram(ram_address)
+        } else {
+            0xff
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x03ff => self.ram_enable = (value & 0x0f) == 0x0a,
+            0x0400..=0x07ff => self.ram_bank[0] = value & 0x07,
+            0x0800..=0x0bff => self.ram_bank[1] = value & 0x07,
+            0x0c00..=0x0fff => self.flash_enable = (value & 0x01) != 0,
+            0x1000..=0x1fff => self.flash_write_enable = (value & 0x01) != 0,
+            0x2000..=0x27ff => self.rom_bank[0] = value & 0x7f,
+            0x2800..=0x2fff => self.flash_select[0] = value == 0x08,
+            0x3000..=0x37ff => self.rom_bank[1] = value & 0x7f,
+            0x3800..=0x3fff => self.flash_select[1] = value == 0x08,
+            0x4000..=0x7fff => {
+                if self.flash_select[MapperMBC6::window(address)] {
+                    self.write_flash(address, value);
+                }
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.ram_enable && self.cartridge.has_ram() {
+            let ram_address = self.ram_address(address);
+            self.cartridge.write_ram(ram_address, value);
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+
+    fn has_battery(&self) -> bool {
+        true
+    }
+
+    fn battery_dirty(&self) -> bool {
+        self.cartridge.is_ram_dirty() || self.flash_dirty
+    }
+
+    fn load_battery(&mut self, data: &[u8]) {
+        let (ram, flash) = data.split_at(self.cartridge.get_ram_length().min(data.len()));
+
+        self.cartridge.load_ram(ram);
+
+        if flash.len() != MBC6_FLASH_SIZE {
+            println!("WARN: flash data is {} bytes, expected {}", flash.len(), MBC6_FLASH_SIZE);
+        }
+
+        let length = flash.len().min(MBC6_FLASH_SIZE);
+        self.flash[..length].copy_from_slice(&flash[..length]);
+
+        self.flash_dirty = false;
+    }
+
+    fn save_battery(&mut self) -> Vec<u8> {
+        let mut data = self.cartridge.save_ram();
+        data.extend_from_slice(&self.flash);
+
+        self.flash_dirty = false;
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapper() -> MapperMBC6 {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0x20;
+        rom[0x149] = 0x03;
+
+        let mut mapper = MapperMBC6::new(Cartridge::from_bytes(rom).unwrap());
+        mapper.write_rom(0x0c00, 0x01);
+        mapper.write_rom(0x1000, 0x01);
+        mapper.write_rom(0x2800, 0x08);
+        mapper
+    }
+
+    fn write(mapper: &mut MapperMBC6, bank: u8, address: u16, value: u8) {
+        mapper.write_rom(0x2000, bank);
+        mapper.write_rom(0x4000 | (address & 0x1fff), value);
+    }
+
+    fn read(mapper: &mut MapperMBC6, bank: u8, address: u16) -> u8 {
+        mapper.write_rom(0x2000, bank);
+        mapper.read_rom(0x4000 | (address & 0x1fff))
+    }
+
+    fn command(mapper: &mut MapperMBC6, value: u8) {
+        write(mapper, 0x02, 0x1555, 0xaa);
+        write(mapper, 0x01, 0x0aaa, 0x55);
+        write(mapper, 0x02, 0x1555, value);
+    }
+
+    fn program(mapper: &mut MapperMBC6, bank: u8, address: u16, value: u8) {
+        command(mapper, 0xa0);
+        write(mapper, bank, address, value);
+    }
+
+    #[test]
+    fn program_clears_bits() {
+        let mut mapper = mapper();
+
+        program(&mut mapper, 0x10, 0x0123, 0x5a);
+        assert_eq!(read(&mut mapper, 0x10, 0x0123), 0x5a);
+
+        program(&mut mapper, 0x10, 0x0123, 0xf0);
+        assert_eq!(read(&mut mapper, 0x10, 0x0123), 0x50);
+        assert!(mapper.battery_dirty());
+
+        mapper.write_rom(0x1000, 0x00);
+        program(&mut mapper, 0x10, 0x0124, 0x00);
+        assert_eq!(read(&mut mapper, 0x10, 0x0124), 0xff);
+    }
+
+    #[test]
+    fn sector_erase_only_clears_its_sector() {
+        let mut mapper = mapper();
+
+        program(&mut mapper, 0x10, 0x0123, 0x5a);
+        program(&mut mapper, 0x20, 0x0123, 0x5a);
+
+        command(&mut mapper, 0x80);
+        write(&mut mapper, 0x02, 0x1555, 0xaa);
+        write(&mut mapper, 0x01, 0x0aaa, 0x55);
+        write(&mut mapper, 0x11, 0x0000, 0x30);
+
+        assert_eq!(read(&mut mapper, 0x10, 0x0123), 0xff);
+        assert_eq!(read(&mut mapper, 0x20, 0x0123), 0x5a);
+    }
+
+    #[test]
+    fn chip_erase_clears_everything() {
+        let mut mapper = mapper();
+
+        program(&mut mapper, 0x10, 0x0123, 0x5a);
+        program(&mut mapper, 0x7f, 0x1fff, 0x00);
+
+        command(&mut mapper, 0x80);
+        command(&mut mapper, 0x10);
+
+        assert_eq!(read(&mut mapper, 0x10, 0x0123), 0xff);
+        assert_eq!(read(&mut mapper, 0x7f, 0x1fff), 0xff);
+    }
+}
@@ -0,0 +1,373 @@
+use super::super::cartridge::Cartridge;
+use super::Mapper;
+
+pub const MBC7_ACCELEROMETER_CENTER: u16 = 0x81d0;
+pub const MBC7_ACCELEROMETER_GRAVITY: f32 = 112.0;
+
+const EEPROM_WORDS: usize = 0x80;
+const EEPROM_COMMAND_BITS: u8 = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+enum EepromState {
+    IDLE,
+    COMMAND,
+    READ,
+    WRITE,
+    WRITEALL,
+}
+
+struct Eeprom {
+    state: EepromState,
+    cs: bool,
+    clk: bool,
+    di: bool,
+    dout: bool,
+    write_enable: bool,
+    shift: u16,
+    count: u8,
+    address: usize,
+}
+
+impl Eeprom {
+    fn new() -> Eeprom {
+        Eeprom {
+            state: EepromState::IDLE,
+            cs: false,
+            clk: false,
+            di: false,
+            dout: true,
+            write_enable: false,
+            shift: 0,
+            count: 0,
+            address: 0,
+        }
+    }
+
+    fn read_word(cartridge: &Cartridge, address: usize) -> u16 {
+        let address = (address % EEPROM_WORDS) * 2;
+        u16::from_le_bytes([cartridge.read_ram(address), cartridge.read_ram(address + 1)])
+    }
+
+    fn write_word(cartridge: &mut Cartridge, address: usize, value: u16) {
+        let address = (address % EEPROM_WORDS) * 2;
+        let bytes = value.to_le_bytes();
+
+        cartridge.write_ram(address, bytes[0]);
+        cartridge.write_ram(address + 1, bytes[1]);
+    }
+
+    fn read(&self) -> u8 {
+        ((self.cs as u8) << 7) | ((self.clk as u8) << 6) | ((self.di as u8) << 1) | self.dout as u8
+    }
+
+    fn write(&mut self, cartridge: &mut Cartridge, value: u8) {
+        let cs = (value & 0x80) != 0;
+        let clk = (value & 0x40) != 0;
+        let di = (value & 0x02) != 0;
+
+        if !cs {
+            self.state = EepromState::IDLE;
+            self.dout = true;
+        } else if clk && !self.clk {
+            self.clock(cartridge, di);
+        }
+
+        self.cs = cs;
+        self.clk = clk;
+        self.di = di;
+    }
+
+    fn clock(&mut self, cartridge: &mut Cartridge, di: bool) {
+        match self.state {
+            EepromState::IDLE => {
+                if di {
+                    self.state = EepromState::COMMAND;
+                    self.shift = 0;
+                    self.count = 0;
+                }
+            },
+            EepromState::COMMAND => {
+                self.shift = (self.shift << 1) | di as u16;
+                self.count += 1;
+
+                if self.count == EEPROM_COMMAND_BITS {
+                    self.command(cartridge);
+                }
+            },
+            EepromState::READ => {
+                self.dout = (self.shift & 0x8000) != 0;
+                self.shift <<= 1;
+                self.count += 1;
+
+                if self.count == 16 {
+                    self.address = (self.address + 1) % EEPROM_WORDS;
+                    self.shift = Eeprom::read_word(cartridge, self.address);
+                    self.count = 0;
+                }
+            },
+            EepromState::WRITE | EepromState::WRITEALL => {
+                self.shift = (self.shift << 1) | di as u16;
+                self.count += 1;
+
+                if self.count == 16 {
+                    if self.write_enable {
+                        if self.state == EepromState::WRITEALL {
+                            for address in 0..EEPROM_WORDS {
+                                Eeprom::write_word(cartridge, address, self.shift);
+                            }
+                        } else {
+                            Eeprom::write_word(cartridge, self.address, self.shift);
+                        }
+                    }
+
+                    self.state = EepromState::IDLE;
+                    self.dout = true;
+                }
+            },
+        }
+    }
+
+    fn command(&mut self, cartridge: &mut Cartridge) {
+        let opcode = self.shift >> 8;
+        let address = self.shift as usize & 0xff;
+
+        self.address = address % EEPROM_WORDS;
+        self.shift = 0;
+        self.count = 0;
+        self.state = EepromState::IDLE;
+
+        match opcode {
+            0b10 => {
+                self.shift = Eeprom::read_word(cartridge, self.address);
+                self.dout = false;
+                self.state = EepromState::READ;
+            },
+            0b01 => self.state = EepromState::WRITE,
+            0b11 => {
+                if self.write_enable {
+                    Eeprom::write_word(cartridge, self.address, 0xffff);
+                }
+            },
+            _ => match address >> 6 {
+                0b00 => self.write_enable = false,
+                0b01 => self.state = EepromState::WRITEALL,
+                0b10 => {
+                    if self.write_enable {
+                        for address in 0..EEPROM_WORDS {
+                            Eeprom::write_word(cartridge, address, 0xffff);
+                        }
+                    }
+                },
+                _ => self.write_enable = true,
+            },
+        }
+    }
+}
+
+pub struct MapperMBC7 {
+    cartridge: Cartridge,
+    ram_enable_1: bool,
+    ram_enable_2: bool,
+    rom_bank: u8,
+    tilt_x: f32,
+    tilt_y: f32,
+    accelerometer_x: u16,
+    accelerometer_y: u16,
+    accelerometer_erased: bool,
+    eeprom: Eeprom,
+}
+
+impl MapperMBC7 {
+    pub fn new(cartridge: Cartridge) -> MapperMBC7 {
+        MapperMBC7 {
+            cartridge,
+            ram_enable_1: false,
+            ram_enable_2: false,
+            rom_bank: 1,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            accelerometer_x: 0x8000,
+            accelerometer_y: 0x8000,
+            accelerometer_erased: false,
+            eeprom: Eeprom::new(),
+        }
+    }
+
+    fn accelerometer(tilt: f32) -> u16 {
+        (MBC7_ACCELEROMETER_CENTER as f32 - (tilt * MBC7_ACCELEROMETER_GRAVITY)) as u16
+    }
+}
+
+impl Mapper for MapperMBC7 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank as usize
+        } else {
+            0
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        if !self.ram_enable_1 || !self.ram_enable_2 || address >= 0xb000 {
+            return 0xff;
+        }
+
+        match (address >> 4) & 0x0f {
+            0x2 => self.accelerometer_x as u8,
+            0x3 => (self.accelerometer_x >> 8) as u8,
+            0x4 => self.accelerometer_y as u8,
+            0x5 => (self.accelerometer_y >> 8) as u8,
+            0x6 => 0x00,
+            0x8 => self.eeprom.read(),
+            _ => 0xff,
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1fff => {
+                self.ram_enable_1 = value == 0x0a;
+
+                if !self.ram_enable_1 {
+                    self.ram_enable_2 = false;
+                }
+            },
+            0x2000..=0x3fff => {
+                self.rom_bank = value & 0x7f;
+            },
+            0x4000..=0x5fff => {
+                self.ram_enable_2 = self.ram_enable_1 && value == 0x40;
+            },
+            0x6000..=0x7fff => {},
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if !self.ram_enable_1 || !self.ram_enable_2 || address >= 0xb000 {
+            return;
+        }
+
+        match (address >> 4) & 0x0f {
+            0x0 if value == 0x55 => {
+                self.accelerometer_x = 0x8000;
+                self.accelerometer_y = 0x8000;
+                self.accelerometer_erased = true;
+            },
+            0x1 if value == 0xaa && self.accelerometer_erased => {
+                self.accelerometer_x = MapperMBC7::accelerometer(self.tilt_x);
+                self.accelerometer_y = MapperMBC7::accelerometer(self.tilt_y);
+                self.accelerometer_erased = false;
+            },
+            0x8 => self.eeprom.write(&mut self.cartridge, value),
+            _ => {},
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+
+    fn set_tilt(&mut self, x: f32, y: f32) {
+        self.tilt_x = x.clamp(-1.0, 1.0);
+        self.tilt_y = y.clamp(-1.0, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapper() -> MapperMBC7 {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0x22;
+
+        let mut mapper = MapperMBC7::new(Cartridge::from_bytes(rom).unwrap());
+        mapper.write_rom(0x0000, 0x0a);
+        mapper.write_rom(0x4000, 0x40);
+        mapper
+    }
+
+    fn clock_bit(mapper: &mut MapperMBC7, bit: bool) -> u8 {
+        let di = if bit { 0x02 } else { 0x00 };
+
+        mapper.write_ram(0xa080, 0x80 | di);
+        mapper.write_ram(0xa080, 0xc0 | di);
+        mapper.read_ram(0xa080) & 0x01
+    }
+
+    fn command(mapper: &mut MapperMBC7, opcode: u16, address: u16) {
+        mapper.write_ram(0xa080, 0x00);
+        clock_bit(mapper, true);
+
+        let command = (opcode << 8) | address;
+
+        for i in (0..10).rev() {
+            clock_bit(mapper, (command >> i) & 0x01 != 0);
+        }
+    }
+
+    fn write_word(mapper: &mut MapperMBC7, address: u16, value: u16) {
+        command(mapper, 0b01, address);
+
+        for i in (0..16).rev() {
+            clock_bit(mapper, (value >> i) & 0x01 != 0);
+        }
+
+        mapper.write_ram(0xa080, 0x00);
+    }
+
+    fn read_word(mapper: &mut MapperMBC7, address: u16) -> u16 {
+        command(mapper, 0b10, address);
+
+        let value = (0..16).fold(0, |value, _| (value << 1) | clock_bit(mapper, false) as u16);
+
+        mapper.write_ram(0xa080, 0x00);
+        value
+    }
+
+    #[test]
+    fn eeprom_writes_need_ewen() {
+        let mut mapper = mapper();
+
+        write_word(&mut mapper, 0x05, 0xbeef);
+        assert_eq!(read_word(&mut mapper, 0x05), 0xffff);
+
+        command(&mut mapper, 0b00, 0xc0);
+        write_word(&mut mapper, 0x05, 0xbeef);
+        assert_eq!(read_word(&mut mapper, 0x05), 0xbeef);
+        assert_eq!(read_word(&mut mapper, 0x06), 0xffff);
+
+        command(&mut mapper, 0b00, 0x00);
+        write_word(&mut mapper, 0x05, 0x1234);
+        assert_eq!(read_word(&mut mapper, 0x05), 0xbeef);
+    }
+
+    #[test]
+    fn accelerometer_is_latched_after_an_erase() {
+        let mut mapper = mapper();
+        let read = |mapper: &mut MapperMBC7, address: u16| (mapper.read_ram(address + 0x10) as u16) << 8 | mapper.read_ram(address) as u16;
+
+        mapper.set_tilt(0.5, -0.25);
+        mapper.write_ram(0xa010, 0xaa);
+        assert_eq!(read(&mut mapper, 0xa020), 0x8000);
+
+        mapper.write_ram(0xa000, 0x55);
+        mapper.write_ram(0xa010, 0xaa);
+        assert_eq!(read(&mut mapper, 0xa020), MBC7_ACCELEROMETER_CENTER - 56);
+        assert_eq!(read(&mut mapper, 0xa040), MBC7_ACCELEROMETER_CENTER + 28);
+
+        mapper.set_tilt(0.0, 0.0);
+        mapper.write_ram(0xa010, 0xaa);
+        assert_eq!(read(&mut mapper, 0xa020), MBC7_ACCELEROMETER_CENTER - 56);
+    }
+}
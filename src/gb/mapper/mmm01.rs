@@ -0,0 +1,235 @@
+use super::super::cartridge::Cartridge;
+use super::Mapper;
+
+pub struct MapperMMM01 {
+    cartridge: Cartridge,
+    ram_enable: bool,
+    mapped: bool,
+    rom_bank_low: u8,
+    rom_bank_mid: u8,
+    rom_bank_high: u8,
+    rom_bank_mask: u8,
+    ram_bank_low: u8,
+    ram_bank_high: u8,
+    ram_bank_mask: u8,
+    mbc1_mode: bool,
+    mbc1_mode_lock: bool,
+    multiplex: bool,
+}
+
+impl MapperMMM01 {
+    pub fn new(cartridge: Cartridge) -> MapperMMM01 {
+        MapperMMM01 {
+            cartridge,
+            ram_enable: false,
+            mapped: false,
+            rom_bank_low: 0,
+            rom_bank_mid: 0,
+            rom_bank_high: 0,
+            rom_bank_mask: 0,
+            ram_bank_low: 0,
+            ram_bank_high: 0,
+            ram_bank_mask: 0,
+            mbc1_mode: false,
+            mbc1_mode_lock: false,
+            multiplex: false,
+        }
+    }
+
+    fn rom_bank_lower(&self) -> usize {
+        if !self.mapped {
+            return self.rom_banks().saturating_sub(2);
+        }
+
+        let mut rom_bank = (self.rom_bank_low & (self.rom_bank_mask << 1)) as usize | ((self.rom_bank_high as usize) << 7);
+
+        if self.multiplex {
+            if self.mbc1_mode {
+                rom_bank |= (self.ram_bank_low as usize) << 5;
+            }
+        } else {
+            rom_bank |= (self.rom_bank_mid as usize) << 5;
+        }
+
+        rom_bank
+    }
+
+    fn rom_bank_upper(&self) -> usize {
+        if !self.mapped {
+            return self.rom_banks().saturating_sub(1);
+        }
+
+        let mut rom_bank_low = self.rom_bank_low as usize;
+
+        if rom_bank_low & !((self.rom_bank_mask as usize) << 1) & 0x1f == 0 {
+            rom_bank_low |= 0x01;
+        }
+
+        let rom_bank_mid = if self.multiplex {
+            self.ram_bank_low
+        } else {
+            self.rom_bank_mid
+        };
+
+        rom_bank_low | ((rom_bank_mid as usize) << 5) | ((self.rom_bank_high as usize) << 7)
+    }
+
+    fn ram_bank(&self) -> usize {
+        let ram_bank_low = if self.multiplex {
+            self.rom_bank_mid
+        } else if self.mbc1_mode {
+            self.ram_bank_low
+        } else {
+            0
+        };
+
+        ram_bank_low as usize | ((self.ram_bank_high as usize) << 2)
+    }
+
+    fn rom_banks(&self) -> usize {
+        self.cartridge.get_mapped_rom_size() / 0x4000
+    }
+}
+
+impl Mapper for MapperMMM01 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank_upper()
+        } else {
+            self.rom_bank_lower()
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = self.ram_bank();
+
+        let ram_size = self.cartridge.get_ram_length().wrapping_sub(1);
+        let bank_address = (bank * 0x2000) | (address & 0x1fff);
+
+        if self.ram_enable && self.cartridge.has_ram() {
+            self.cartridge.read_ram(bank_address & ram_size)
+        } else {
+            0xff
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1fff => {
+                self.ram_enable = (value & 0x0f) == 0x0a;
+
+                if !self.mapped {
+                    self.ram_bank_mask = (value >> 4) & 0x03;
+                    self.mapped = (value & 0x40) != 0;
+                }
+            },
+            0x2000..=0x3fff => {
+                let mask = self.rom_bank_mask << 1;
+                self.rom_bank_low = (self.rom_bank_low & mask) | (value & !mask & 0x1f);
+
+                if !self.mapped {
+                    self.rom_bank_mid = (value >> 5) & 0x03;
+                }
+            },
+            0x4000..=0x5fff => {
+                let mask = self.ram_bank_mask;
+                self.ram_bank_low = (self.ram_bank_low & mask) | (value & !mask & 0x03);
+
+                if !self.mapped {
+                    self.ram_bank_high = (value >> 2) & 0x03;
+                    self.rom_bank_high = (value >> 4) & 0x03;
+                    self.mbc1_mode_lock = (value & 0x40) != 0;
+                }
+            },
+            0x6000..=0x7fff => {
+                if !self.mbc1_mode_lock {
+                    self.mbc1_mode = (value & 0x01) != 0;
+                }
+
+                if !self.mapped {
+                    self.rom_bank_mask = (value >> 2) & 0x0f;
+                    self.multiplex = (value & 0x40) != 0;
+                }
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        let address = address as usize;
+        let bank = self.ram_bank();
+
+        let ram_size = self.cartridge.get_ram_length().wrapping_sub(1);
+        let bank_address = (bank * 0x2000) | (address & 0x1fff);
+
+        if self.ram_enable && self.cartridge.has_ram() {
+            self.cartridge.write_ram(bank_address & ram_size, value);
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapper() -> MapperMMM01 {
+        let mut rom = vec![0; 0x20000];
+        rom[0x18147] = 0x0b;
+        rom[0x18148] = 0x02;
+
+        for bank in 0..8 {
+            rom[bank * 0x4000 + 0x10] = bank as u8;
+        }
+
+        MapperMMM01::new(Cartridge::from_bytes(rom).unwrap())
+    }
+
+    #[test]
+    fn menu_is_mapped_from_the_last_32kb() {
+        let mut mapper = mapper();
+
+        assert_eq!(mapper.read_rom(0x0010), 6);
+        assert_eq!(mapper.read_rom(0x4010), 7);
+
+        mapper.write_rom(0x2000, 0x02);
+        assert_eq!(mapper.read_rom(0x4010), 7);
+
+        mapper.write_rom(0x0000, 0x40);
+        assert_eq!(mapper.read_rom(0x0010), 0);
+        assert_eq!(mapper.read_rom(0x4010), 2);
+    }
+
+    #[test]
+    fn mapping_locks_the_game_bank_bits() {
+        let mut mapper = mapper();
+
+        mapper.write_rom(0x2000, 0x04);
+        mapper.write_rom(0x6000, 0x08);
+        mapper.write_rom(0x0000, 0x40);
+
+        assert_eq!(mapper.read_rom(0x0010), 4);
+        mapper.write_rom(0x2000, 0x00);
+        assert_eq!(mapper.read_rom(0x4010), 5);
+
+        mapper.write_rom(0x0000, 0x00);
+        mapper.write_rom(0x6000, 0x00);
+        mapper.write_rom(0x2000, 0x02);
+
+        assert_eq!(mapper.read_rom(0x0010), 4);
+        assert_eq!(mapper.read_rom(0x4010), 6);
+    }
+}
@@ -0,0 +1,116 @@
+use super::cartridge::{Cartridge, CartridgeMapper};
+use super::error::LoadError;
+use super::image_source::ImageSource;
+use super::rtc::RtcMode;
+
+mod camera;
+mod huc1;
+mod huc3;
+mod m161;
+mod mbc1;
+mod mbc2;
+mod mbc3;
+mod mbc5;
+mod mbc6;
+mod mbc7;
+mod mmm01;
+mod none;
+mod sachen;
+mod tama5;
+mod wisdomtree;
+
+pub use self::camera::MapperPocketCamera;
+pub use self::huc1::MapperHuC1;
+pub use self::huc3::MapperHuC3;
+pub use self::m161::MapperM161;
+pub use self::mbc1::MapperMBC1;
+pub use self::mbc2::MapperMBC2;
+pub use self::mbc3::MapperMBC3;
+pub use self::mbc5::MapperMBC5;
+pub use self::mbc6::MapperMBC6;
+pub use self::mbc7::MapperMBC7;
+pub use self::mmm01::MapperMMM01;
+pub use self::none::MapperNone;
+pub use self::sachen::MapperSachen;
+pub use self::tama5::MapperTAMA5;
+pub use self::wisdomtree::MapperWisdomTree;
+
+pub trait Mapper {
+    fn read_rom(&mut self, address: u16) -> u8;
+    fn read_ram(&mut self, address: u16) -> u8;
+
+    fn write_rom(&mut self, address: u16, value: u8);
+    fn write_ram(&mut self, address: u16, value: u8);
+
+    fn cartridge(&self) -> &Cartridge;
+    fn cartridge_mut(&mut self) -> &mut Cartridge;
+
+    fn has_battery(&self) -> bool {
+        self.cartridge().get_type().has_battery() && self.cartridge().has_ram()
+    }
+
+    fn battery_dirty(&self) -> bool {
+        self.cartridge().is_ram_dirty()
+    }
+
+    fn load_battery(&mut self, data: &[u8]) {
+        self.cartridge_mut().load_ram(data);
+    }
+
+    fn save_battery(&mut self) -> Vec<u8> {
+        self.cartridge_mut().save_ram()
+    }
+
+    fn info(&self) {
+        self.cartridge().info();
+    }
+
+    fn tick(&mut self, _cycles: usize) {}
+
+    fn rumble(&self) -> bool {
+        false
+    }
+
+    fn set_tilt(&mut self, _x: f32, _y: f32) {}
+
+    fn set_image_source(&mut self, _image_source: Box<dyn ImageSource + Send>) {}
+
+    fn tone(&self) -> bool {
+        false
+    }
+
+    fn ir_led(&self) -> bool {
+        false
+    }
+
+    fn set_ir_input(&mut self, _light: bool) {}
+
+    fn set_rtc_mode(&mut self, _mode: RtcMode) {}
+
+    fn skip_bootrom(&mut self) {}
+}
+
+impl dyn Mapper {
+    pub fn new(cartridge: Cartridge) -> Result<Box<dyn Mapper + Send>, LoadError> {
+        match cartridge.get_type().get_mapper() {
+            CartridgeMapper::NONE => Ok(Box::new(MapperNone::new(cartridge))),
+            CartridgeMapper::MBC1 => Ok(Box::new(MapperMBC1::new(cartridge, false))),
+            CartridgeMapper::MBC1M => Ok(Box::new(MapperMBC1::new(cartridge, true))),
+            CartridgeMapper::MBC2 => Ok(Box::new(MapperMBC2::new(cartridge))),
+            CartridgeMapper::MBC3 => Ok(Box::new(MapperMBC3::new(cartridge))),
+            CartridgeMapper::MBC5 => Ok(Box::new(MapperMBC5::new(cartridge))),
+            CartridgeMapper::MBC6 => Ok(Box::new(MapperMBC6::new(cartridge))),
+            CartridgeMapper::MBC7 => Ok(Box::new(MapperMBC7::new(cartridge))),
+            CartridgeMapper::MMM01 => Ok(Box::new(MapperMMM01::new(cartridge))),
+            CartridgeMapper::HUC1 => Ok(Box::new(MapperHuC1::new(cartridge))),
+            CartridgeMapper::HUC3 => Ok(Box::new(MapperHuC3::new(cartridge))),
+            CartridgeMapper::TAMA5 => Ok(Box::new(MapperTAMA5::new(cartridge))),
+            CartridgeMapper::POCKETCAMERA => Ok(Box::new(MapperPocketCamera::new(cartridge))),
+            CartridgeMapper::WISDOMTREE => Ok(Box::new(MapperWisdomTree::new(cartridge))),
+            CartridgeMapper::SACHENMMC1 => Ok(Box::new(MapperSachen::new(cartridge, false))),
+            CartridgeMapper::SACHENMMC2 => Ok(Box::new(MapperSachen::new(cartridge, true))),
+            CartridgeMapper::M161 => Ok(Box::new(MapperM161::new(cartridge))),
+            _ => Err(LoadError::UNSUPPORTEDMAPPER(cartridge.get_type_byte())),
+        }
+    }
+}
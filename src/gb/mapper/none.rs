@@ -0,0 +1,48 @@
+use super::super::cartridge::Cartridge;
+use super::Mapper;
+
+pub struct MapperNone {
+    cartridge: Cartridge,
+}
+
+impl MapperNone {
+    pub fn new(cartridge: Cartridge) -> MapperNone {
+        MapperNone {
+            cartridge,
+        }
+    }
+}
+
+impl Mapper for MapperNone {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        self.cartridge.read_rom(address as usize)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        if self.cartridge.has_ram() {
+            let ram_size = self.cartridge.get_ram_length() - 1;
+            self.cartridge.read_ram((address as usize & 0x1fff) & ram_size)
+        } else {
+            0xff
+        }
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        self.cartridge.write_rom(address as usize, value);
+    }
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if self.cartridge.has_ram() {
+            let ram_size = self.cartridge.get_ram_length() - 1;
+            self.cartridge.write_ram((address as usize & 0x1fff) & ram_size, value);
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+}
@@ -0,0 +1,182 @@
+use super::super::cartridge::{unscramble_sachen, Cartridge};
+use super::Mapper;
+
+const SACHEN_UNLOCK_READS: u8 = 0x31;
+
+#[derive(Clone, Copy, PartialEq)]
+enum SachenLock {
+    DMG,
+    CGB,
+    UNLOCKED,
+}
+
+pub struct MapperSachen {
+    cartridge: Cartridge,
+    mmc2: bool,
+    lock: SachenLock,
+    lock_reads: u8,
+    base_bank: u8,
+    bank_mask: u8,
+    rom_bank: u8,
+}
+
+impl MapperSachen {
+    pub fn new(cartridge: Cartridge, mmc2: bool) -> MapperSachen {
+        MapperSachen {
+            cartridge,
+            mmc2,
+            lock: SachenLock::DMG,
+            lock_reads: 0,
+            base_bank: 0x30,
+            bank_mask: 0x00,
+            rom_bank: 1,
+        }
+    }
+
+    fn registers_unlocked(&self) -> bool {
+        (self.base_bank & 0x30) == 0x30
+    }
+
+    fn count_lock_read(&mut self) {
+        self.lock_reads += 1;
+
+        if self.lock_reads == SACHEN_UNLOCK_READS {
+            self.lock_reads = 0;
+            self.lock = match self.lock {
+                SachenLock::DMG if self.mmc2 => SachenLock::CGB,
+                _ => SachenLock::UNLOCKED,
+            };
+        }
+    }
+
+    fn logo_address(&self, address: u16) -> u16 {
+        let scrambled = match self.lock {
+            SachenLock::DMG if !self.mmc2 => address | 0x80,
+            SachenLock::CGB => address | 0x80,
+            _ => address,
+        };
+
+        unscramble_sachen(scrambled)
+    }
+}
+
+impl Mapper for MapperSachen {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let mut address = address;
+
+        if (address & 0xff00) == 0x0100 {
+            if self.lock != SachenLock::UNLOCKED {
+                self.count_lock_read();
+            }
+
+            address = self.logo_address(address);
+        }
+
+        let bank = if address >= 0x4000 {
+            (self.rom_bank & !self.bank_mask) | (self.base_bank & self.bank_mask)
+        } else {
+            self.base_bank & self.bank_mask
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank as usize * 0x4000) | (address as usize & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, _address: u16) -> u8 {
+        0xff
+    }
+
+    fn write_rom(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1fff if self.registers_unlocked() => {
+                self.base_bank = value;
+            },
+            0x2000..=0x3fff => {
+                self.rom_bank = if value == 0 { 1 } else { value };
+            },
+            0x4000..=0x5fff if self.registers_unlocked() => {
+                self.bank_mask = value;
+            },
+            _ => {},
+        }
+    }
+
+    fn write_ram(&mut self, _address: u16, _value: u8) {}
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+
+    fn skip_bootrom(&mut self) {
+        self.lock = SachenLock::UNLOCKED;
+        self.lock_reads = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::cartridge::CartridgeMapper;
+
+    fn mapper(mmc2: bool) -> MapperSachen {
+        let mut rom = vec![0; 0x40000];
+
+        for bank in 0..0x10 {
+            rom[(bank * 0x4000) + 0x2000] = bank as u8;
+        }
+
+        rom[0x0104] = 0xaa;
+        rom[unscramble_sachen(0x0184) as usize] = 0x55;
+
+        let mut cartridge = Cartridge::from_bytes(rom).unwrap();
+        cartridge.set_mapper(if mmc2 { CartridgeMapper::SACHENMMC2 } else { CartridgeMapper::SACHENMMC1 });
+
+        MapperSachen::new(cartridge, mmc2)
+    }
+
+    fn read_logo(mapper: &mut MapperSachen, reads: u8) -> u8 {
+        (0..reads).fold(0, |_, _| mapper.read_rom(0x0104))
+    }
+
+    #[test]
+    fn mmc1_logo_is_scrambled_until_unlocked() {
+        let mut mapper = mapper(false);
+
+        assert_eq!(read_logo(&mut mapper, SACHEN_UNLOCK_READS - 1), 0x55);
+        assert_eq!(read_logo(&mut mapper, 1), 0xaa);
+        assert_eq!(read_logo(&mut mapper, SACHEN_UNLOCK_READS), 0xaa);
+    }
+
+    #[test]
+    fn mmc2_logo_is_scrambled_only_for_the_cgb_boot() {
+        let mut mapper = mapper(true);
+
+        assert_eq!(read_logo(&mut mapper, SACHEN_UNLOCK_READS - 1), 0xaa);
+        assert_eq!(read_logo(&mut mapper, SACHEN_UNLOCK_READS), 0x55);
+        assert_eq!(read_logo(&mut mapper, 1), 0xaa);
+    }
+
+    #[test]
+    fn base_and_mask_select_the_outer_bank() {
+        let mut mapper = mapper(false);
+        mapper.skip_bootrom();
+
+        mapper.write_rom(0x2000, 0x05);
+        assert_eq!(mapper.read_rom(0x2000), 0x00);
+        assert_eq!(mapper.read_rom(0x6000), 0x05);
+
+        mapper.write_rom(0x4000, 0x0c);
+        mapper.write_rom(0x0000, 0x08);
+        assert_eq!(mapper.read_rom(0x2000), 0x08);
+        assert_eq!(mapper.read_rom(0x6000), 0x09);
+
+        mapper.write_rom(0x4000, 0x00);
+        mapper.write_rom(0x0000, 0x00);
+        assert_eq!(mapper.read_rom(0x6000), 0x09);
+    }
+}
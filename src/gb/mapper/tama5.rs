@@ -0,0 +1,379 @@
+use super::super::cartridge::Cartridge;
+use super::super::rtc::{RtcClock, RtcMode};
+use super::Mapper;
+
+pub const TAMA5_RTC_SAVE_SIZE: usize = 15;
+
+const TAMA5_ROM_BANK_LOW: usize = 0x0;
+const TAMA5_ROM_BANK_HIGH: usize = 0x1;
+const TAMA5_DATA_LOW: usize = 0x4;
+const TAMA5_DATA_HIGH: usize = 0x5;
+const TAMA5_ADDRESS_HIGH: usize = 0x6;
+const TAMA5_ADDRESS_LOW: usize = 0x7;
+const TAMA5_STATUS: usize = 0xa;
+const TAMA5_READ_LOW: usize = 0xc;
+const TAMA5_READ_HIGH: usize = 0xd;
+
+#[derive(Clone, Copy)]
+struct Tama5Rtc {
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+    weekday: u8,
+    day: u8,
+    month: u8,
+    year: u8,
+}
+
+impl Tama5Rtc {
+    fn new() -> Tama5Rtc {
+        Tama5Rtc {
+            seconds: 0,
+            minutes: 0,
+            hours: 0,
+            weekday: 0,
+            day: 1,
+            month: 1,
+            year: 0,
+        }
+    }
+
+    fn days_in_month(&self) -> u8 {
+        match self.month {
+            2 if self.year % 4 == 0 => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    fn normalise(&mut self) {
+        self.seconds = self.seconds.min(59);
+        self.minutes = self.minutes.min(59);
+        self.hours = self.hours.min(23);
+        self.weekday %= 7;
+        self.month = self.month.clamp(1, 12);
+        self.day = self.day.clamp(1, self.days_in_month());
+        self.year %= 100;
+    }
+
+    fn advance(&mut self, seconds: u64) {
+        self.normalise();
+
+        let total = self.seconds as u64 + (self.minutes as u64 * 60) + (self.hours as u64 * 3600) + seconds;
+
+        self.seconds = (total % 60) as u8;
+        self.minutes = ((total / 60) % 60) as u8;
+        self.hours = ((total / 3600) % 24) as u8;
+
+        for _ in 0..(total / 86400) {
+            self.weekday = (self.weekday + 1) % 7;
+            self.day += 1;
+
+            if self.day > self.days_in_month() {
+                self.day = 1;
+                self.month += 1;
+
+                if self.month > 12 {
+                    self.month = 1;
+                    self.year = (self.year + 1) % 100;
+                }
+            }
+        }
+    }
+
+    fn read(&mut self, register: u8) -> u8 {
+        self.normalise();
+
+        match register {
+            0x0 => self.seconds % 10,
+            0x1 => self.seconds / 10,
+            0x2 => self.minutes % 10,
+            0x3 => self.minutes / 10,
+            0x4 => self.hours % 10,
+            0x5 => self.hours / 10,
+            0x6 => self.weekday,
+            0x7 => self.day % 10,
+            0x8 => self.day / 10,
+            0x9 => self.month % 10,
+            0xa => self.month / 10,
+            0xb => self.year % 10,
+            0xc => self.year / 10,
+            _ => 0x0,
+        }
+    }
+
+    // games set the clock one digit at a time, so a half written field is kept as is until it is used
+    fn write(&mut self, register: u8, value: u8) {
+        let value = value & 0x0f;
+
+        match register {
+            0x0 => self.seconds = (self.seconds / 10 * 10) + value,
+            0x1 => self.seconds = (self.seconds % 10) + (value * 10),
+            0x2 => self.minutes = (self.minutes / 10 * 10) + value,
+            0x3 => self.minutes = (self.minutes % 10) + (value * 10),
+            0x4 => self.hours = (self.hours / 10 * 10) + value,
+            0x5 => self.hours = (self.hours % 10) + (value * 10),
+            0x6 => self.weekday = value % 7,
+            0x7 => self.day = (self.day / 10 * 10) + value,
+            0x8 => self.day = (self.day % 10) + (value * 10),
+            0x9 => self.month = (self.month / 10 * 10) + value,
+            0xa => self.month = (self.month % 10) + (value * 10),
+            0xb => self.year = (self.year / 10 * 10) + value,
+            0xc => self.year = (self.year % 10) + (value * 10),
+            _ => {},
+        }
+    }
+}
+
+pub struct MapperTAMA5 {
+    cartridge: Cartridge,
+    register: usize,
+    registers: [u8; 0x10],
+    output: u8,
+    rtc: Tama5Rtc,
+    rtc_clock: RtcClock,
+    rtc_dirty: bool,
+}
+
+impl MapperTAMA5 {
+    pub fn new(cartridge: Cartridge) -> MapperTAMA5 {
+        MapperTAMA5 {
+            cartridge,
+            register: 0,
+            registers: [0; 0x10],
+            output: 0,
+            rtc: Tama5Rtc::new(),
+            rtc_clock: RtcClock::new(),
+            rtc_dirty: false,
+        }
+    }
+
+    fn rom_bank(&self) -> usize {
+        (self.registers[TAMA5_ROM_BANK_LOW] as usize & 0x0f) | ((self.registers[TAMA5_ROM_BANK_HIGH] as usize & 0x01) << 4)
+    }
+
+    fn update_rtc(&mut self) {
+        let elapsed = self.rtc_clock.elapsed();
+
+        if elapsed > 0 {
+            self.rtc.advance(elapsed);
+            self.rtc_dirty |= self.rtc_clock.get_mode() == RtcMode::EMULATED;
+        }
+    }
+
+    fn execute(&mut self) {
+        let address = ((self.registers[TAMA5_ADDRESS_HIGH] as usize & 0x01) << 4) | (self.registers[TAMA5_ADDRESS_LOW] as usize & 0x0f);
+        let data = ((self.registers[TAMA5_DATA_HIGH] & 0x0f) << 4) | (self.registers[TAMA5_DATA_LOW] & 0x0f);
+
+        match self.registers[TAMA5_ADDRESS_HIGH] >> 1 {
+            0x0 => self.cartridge.write_ram(address, data),
+            0x1 => self.output = self.cartridge.read_ram(address),
+            0x2 => {
+                self.update_rtc();
+
+                if address == 0x00 {
+                    self.rtc_clock.reset_subsecond();
+                }
+
+                self.rtc.write(address as u8 & 0x0f, data);
+                self.rtc_dirty = true;
+            },
+            0x4 => {
+                self.update_rtc();
+                self.output = self.rtc.read(address as u8 & 0x0f);
+            },
+            command => println!("WARN: unknown TAMA5 command {:#x}", command),
+        }
+    }
+}
+
+impl Mapper for MapperTAMA5 {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let address = address as usize;
+        let bank = if address >= 0x4000 {
+            self.rom_bank()
+        } else {
+            0
+        };
+
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (bank * 0x4000) | (address & 0x3fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, address: u16) -> u8 {
+        if address & 0x01 != 0 {
+            return 0xff;
+        }
+
+        match self.register {
+            TAMA5_STATUS => 0xf1,
+            TAMA5_READ_LOW => 0xf0 | (self.output & 0x0f),
+            TAMA5_READ_HIGH => 0xf0 | (self.output >> 4),
+            _ => 0xff,
+        }
+    }
+
+    fn write_rom(&mut self, _: u16, _: u8) {}
+
+    fn write_ram(&mut self, address: u16, value: u8) {
+        if address & 0x01 != 0 {
+            self.register = value as usize & 0x0f;
+            return;
+        }
+
+        self.registers[self.register] = value & 0x0f;
+
+        if self.register == TAMA5_ADDRESS_LOW {
+            self.execute();
+        }
+    }
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+
+    fn battery_dirty(&self) -> bool {
+        self.cartridge.is_ram_dirty() || self.rtc_dirty
+    }
+
+    fn load_battery(&mut self, data: &[u8]) {
+        let ram_size = self.cartridge.get_ram_length();
+        let (ram, footer) = data.split_at(ram_size.min(data.len()));
+
+        self.cartridge.load_ram(ram);
+
+        if footer.len() >= TAMA5_RTC_SAVE_SIZE {
+            let mut timestamp = [0u8; 8];
+            timestamp.copy_from_slice(&footer[0..8]);
+
+            self.rtc = Tama5Rtc {
+                seconds: footer[8],
+                minutes: footer[9],
+                hours: footer[10],
+                weekday: footer[11],
+                day: footer[12],
+                month: footer[13],
+                year: footer[14],
+            };
+            self.rtc.normalise();
+
+            self.rtc_clock.load_timestamp(u64::from_le_bytes(timestamp));
+            self.update_rtc();
+        }
+
+        self.rtc_dirty = false;
+    }
+
+    fn save_battery(&mut self) -> Vec<u8> {
+        self.update_rtc();
+
+        let mut data = self.cartridge.save_ram();
+
+        data.extend_from_slice(&self.rtc_clock.timestamp().to_le_bytes());
+        data.extend_from_slice(&[self.rtc.seconds, self.rtc.minutes, self.rtc.hours, self.rtc.weekday, self.rtc.day, self.rtc.month, self.rtc.year]);
+
+        self.rtc_dirty = false;
+        data
+    }
+
+    fn tick(&mut self, cycles: usize) {
+        if self.rtc_clock.tick(cycles) {
+            self.update_rtc();
+        }
+    }
+
+    fn set_rtc_mode(&mut self, mode: RtcMode) {
+        self.rtc_clock.set_mode(mode);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::rtc::RTC_CYCLES_PER_SECOND;
+    use super::*;
+
+    fn mapper() -> MapperTAMA5 {
+        let mut rom = vec![0; 0x8000];
+        rom[0x147] = 0xfd;
+
+        let mut mapper = MapperTAMA5::new(Cartridge::from_bytes(rom).unwrap());
+        mapper.set_rtc_mode(RtcMode::EMULATED);
+        mapper
+    }
+
+    fn write_register(mapper: &mut MapperTAMA5, register: usize, value: u8) {
+        mapper.write_ram(0xa001, register as u8);
+        mapper.write_ram(0xa000, value);
+    }
+
+    fn write_rtc(mapper: &mut MapperTAMA5, register: u8, value: u8) {
+        write_register(mapper, TAMA5_DATA_LOW, value);
+        write_register(mapper, TAMA5_DATA_HIGH, 0);
+        write_register(mapper, TAMA5_ADDRESS_HIGH, 0x2 << 1);
+        write_register(mapper, TAMA5_ADDRESS_LOW, register);
+    }
+
+    fn read_rtc(mapper: &mut MapperTAMA5, register: u8) -> u8 {
+        write_register(mapper, TAMA5_ADDRESS_HIGH, 0x4 << 1);
+        write_register(mapper, TAMA5_ADDRESS_LOW, register);
+        mapper.write_ram(0xa001, TAMA5_READ_LOW as u8);
+        mapper.read_ram(0xa000) & 0x0f
+    }
+
+    #[test]
+    fn clock_digits_are_written_one_at_a_time() {
+        let mut mapper = mapper();
+
+        write_rtc(&mut mapper, 0x4, 0);
+        write_rtc(&mut mapper, 0x5, 2);
+        write_rtc(&mut mapper, 0x4, 9);
+        write_rtc(&mut mapper, 0x5, 1);
+
+        assert_eq!((read_rtc(&mut mapper, 0x5), read_rtc(&mut mapper, 0x4)), (1, 9));
+
+        write_rtc(&mut mapper, 0x9, 0);
+        write_rtc(&mut mapper, 0xa, 1);
+        write_rtc(&mut mapper, 0x9, 9);
+        write_rtc(&mut mapper, 0xa, 0);
+
+        assert_eq!((read_rtc(&mut mapper, 0xa), read_rtc(&mut mapper, 0x9)), (0, 9));
+    }
+
+    #[test]
+    fn out_of_range_time_is_normalised_when_read() {
+        let mut mapper = mapper();
+
+        write_rtc(&mut mapper, 0x4, 9);
+        write_rtc(&mut mapper, 0x5, 2);
+
+        assert_eq!((read_rtc(&mut mapper, 0x5), read_rtc(&mut mapper, 0x4)), (2, 3));
+    }
+
+    #[test]
+    fn clock_rolls_over_into_the_next_month() {
+        let mut mapper = mapper();
+
+        write_rtc(&mut mapper, 0x7, 8);
+        write_rtc(&mut mapper, 0x8, 2);
+        write_rtc(&mut mapper, 0x9, 2);
+        write_rtc(&mut mapper, 0xb, 1);
+        write_rtc(&mut mapper, 0x4, 3);
+        write_rtc(&mut mapper, 0x5, 2);
+        write_rtc(&mut mapper, 0x2, 9);
+        write_rtc(&mut mapper, 0x3, 5);
+        write_rtc(&mut mapper, 0x1, 5);
+        write_rtc(&mut mapper, 0x0, 9);
+
+        mapper.tick(RTC_CYCLES_PER_SECOND);
+
+        assert_eq!((read_rtc(&mut mapper, 0x8), read_rtc(&mut mapper, 0x7)), (0, 1));
+        assert_eq!((read_rtc(&mut mapper, 0xa), read_rtc(&mut mapper, 0x9)), (0, 3));
+        assert_eq!((read_rtc(&mut mapper, 0x5), read_rtc(&mut mapper, 0x4)), (0, 0));
+    }
+}
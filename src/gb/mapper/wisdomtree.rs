@@ -0,0 +1,76 @@
+use super::super::cartridge::Cartridge;
+use super::Mapper;
+
+pub struct MapperWisdomTree {
+    cartridge: Cartridge,
+    rom_bank: u8,
+}
+
+impl MapperWisdomTree {
+    pub fn new(cartridge: Cartridge) -> MapperWisdomTree {
+        MapperWisdomTree {
+            cartridge,
+            rom_bank: 0,
+        }
+    }
+}
+
+impl Mapper for MapperWisdomTree {
+    fn read_rom(&mut self, address: u16) -> u8 {
+        let rom_size = self.cartridge.get_mapped_rom_size() - 1;
+        let bank_address = (self.rom_bank as usize * 0x8000) | (address as usize & 0x7fff);
+        self.cartridge.read_rom(bank_address & rom_size)
+    }
+
+    fn read_ram(&mut self, _address: u16) -> u8 {
+        0xff
+    }
+
+    fn write_rom(&mut self, address: u16, _value: u8) {
+        if address < 0x4000 {
+            self.rom_bank = address as u8;
+        }
+    }
+
+    fn write_ram(&mut self, _address: u16, _value: u8) {}
+
+    fn cartridge(&self) -> &Cartridge {
+        &self.cartridge
+    }
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge {
+        &mut self.cartridge
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::cartridge::CartridgeMapper;
+
+    #[test]
+    fn write_address_selects_the_32kb_bank() {
+        let mut rom = vec![0; 0x20000];
+
+        for bank in 0..4 {
+            rom[(bank * 0x8000) + 0x0200] = bank as u8;
+            rom[(bank * 0x8000) + 0x4200] = 0x10 | bank as u8;
+        }
+
+        let mut cartridge = Cartridge::from_bytes(rom).unwrap();
+        cartridge.set_mapper(CartridgeMapper::WISDOMTREE);
+
+        let mut mapper = MapperWisdomTree::new(cartridge);
+        assert_eq!(mapper.read_rom(0x0200), 0x00);
+
+        mapper.write_rom(0x0002, 0xff);
+        assert_eq!(mapper.read_rom(0x0200), 0x02);
+        assert_eq!(mapper.read_rom(0x4200), 0x12);
+
+        mapper.write_rom(0x4003, 0x00);
+        assert_eq!(mapper.read_rom(0x0200), 0x02);
+
+        mapper.write_rom(0x0007, 0x00);
+        assert_eq!(mapper.read_rom(0x0200), 0x03);
+    }
+}
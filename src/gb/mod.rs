@@ -1,50 +1,221 @@
-mod apu;
-mod audio_system;
-mod bus;
-mod cartridge;
-mod controller;
-mod mapper;
-mod ppu;
-mod timer;
-mod video_system;
-mod z80;
-
-use sdl2;
-
-use self::audio_system::AudioSystem;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub mod apu;
+pub mod audio_sink;
+pub mod bus;
+pub mod cartridge;
+pub mod controller;
+pub mod error;
+pub mod gbx;
+pub mod image_source;
+pub mod mapper;
+pub mod model;
+pub mod ppu;
+pub mod rtc;
+pub mod save;
+pub mod timer;
+pub mod video_sink;
+pub mod z80;
+
+use self::audio_sink::AudioSink;
 use self::bus::Bus;
 use self::cartridge::Cartridge;
+use self::controller::Button;
+use self::error::LoadError;
+use self::image_source::ImageSource;
 use self::mapper::Mapper;
-use self::video_system::VideoSystem;
+use self::model::Model;
+use self::ppu::PpuShade;
+use self::rtc::RtcMode;
+use self::video_sink::VideoSink;
 use self::z80::Z80;
 
+pub const BOOTROM_SIZE: usize = 0x100;
+
+pub struct EmulationResult {
+    pub cycles: usize,
+    pub frame: Option<Box<[PpuShade]>>,
+    pub samples: Vec<i16>,
+}
+
+impl EmulationResult {
+    fn new() -> EmulationResult {
+        EmulationResult {
+            cycles: 0,
+            frame: None,
+            samples: Vec::new(),
+        }
+    }
+}
+
 pub struct Gameboy {
     cpu: Z80,
+    save_filepath: Option<PathBuf>,
 }
 
 impl Gameboy {
-    pub fn new(cartridge_filepath: &str) -> Gameboy {
-        let cartridge = Cartridge::new(cartridge_filepath);
-        let mapper = Mapper::new(cartridge);
+    pub fn new(cartridge_filepath: &str) -> Result<Gameboy, LoadError> {
+        let cartridge = Cartridge::new(cartridge_filepath)?;
+        Gameboy::from_cartridge(cartridge)
+    }
 
-        mapper.info();
+    pub fn from_cartridge(cartridge: Cartridge) -> Result<Gameboy, LoadError> {
+        let mapper = <dyn Mapper>::new(cartridge)?;
 
-        let sdl_context = sdl2::init().unwrap();
+        mapper.info();
 
-        let audio_system = AudioSystem::new(&sdl_context);
-        let video_system = VideoSystem::new(&sdl_context, 160, 144, "rgb");
-        let bus = Bus::new(mapper, audio_system, video_system);
+        let bus = Bus::new(mapper);
 
-        Gameboy {
+        Ok(Gameboy {
             cpu: Z80::new(bus),
+            save_filepath: None,
+        })
+    }
+
+    pub fn set_model(&mut self, model: Model) {
+        self.cpu.bus_mut().set_model(model);
+    }
+
+    pub fn set_bootrom(&mut self, bootrom: Option<Vec<u8>>) -> Result<(), LoadError> {
+        let bootrom = bootrom.unwrap_or_default();
+
+        if !bootrom.is_empty() && bootrom.len() != BOOTROM_SIZE {
+            return Err(LoadError::INVALIDBOOTROM(bootrom.len()));
+        }
+
+        self.cpu.bus_mut().set_bootrom(bootrom.into_boxed_slice());
+        Ok(())
+    }
+
+    pub fn set_rtc_mode(&mut self, mode: RtcMode) {
+        self.cpu.bus_mut().mapper_mut().set_rtc_mode(mode);
+    }
+
+    pub fn has_battery(&self) -> bool {
+        self.cpu.bus().mapper().has_battery()
+    }
+
+    pub fn load_battery(&mut self, data: &[u8]) {
+        self.cpu.bus_mut().mapper_mut().load_battery(data);
+    }
+
+    pub fn save_battery(&mut self) -> Vec<u8> {
+        self.cpu.bus_mut().mapper_mut().save_battery()
+    }
+
+    pub fn load_save(&mut self, save_filepath: &Path) -> io::Result<()> {
+        if !self.has_battery() {
+            return Ok(());
+        }
+
+        if let Some(data) = save::read(save_filepath)? {
+            self.load_battery(&data);
+        }
+
+        self.save_filepath = Some(save_filepath.to_path_buf());
+        Ok(())
+    }
+
+    pub fn flush_save(&mut self) -> io::Result<()> {
+        if !self.cpu.bus().mapper().battery_dirty() {
+            return Ok(());
         }
+
+        if let Some(save_filepath) = self.save_filepath.clone() {
+            let data = self.save_battery();
+            save::write(&save_filepath, &data)?;
+        }
+
+        Ok(())
     }
 
     pub fn run(&mut self) {
         self.cpu.run();
+        self.cpu.bus_mut().apu_mut().flush_samples();
+    }
+
+    pub fn run_frame(&mut self) -> EmulationResult {
+        let mut result = EmulationResult::new();
+
+        while result.frame.is_none() {
+            self.step(&mut result);
+        }
+
+        result
+    }
+
+    pub fn run_cycles(&mut self, cycles: usize) -> EmulationResult {
+        let mut result = EmulationResult::new();
+
+        while result.cycles < cycles {
+            self.step(&mut result);
+        }
+
+        result
+    }
+
+    fn step(&mut self, result: &mut EmulationResult) {
+        let start_cycles = self.cpu.bus().get_cycles();
+
+        self.cpu.run();
+
+        let bus = self.cpu.bus_mut();
+        result.cycles += (bus.get_cycles() - start_cycles) as usize;
+
+        result.samples.extend_from_slice(bus.apu().samples());
+        bus.apu_mut().flush_samples();
+
+        if bus.ppu_mut().get_frame_status() {
+            result.frame = Some(Box::from(bus.ppu().framebuffer()));
+        }
     }
 
     pub fn reset(&mut self) {
         self.cpu.reset();
     }
-}
\ No newline at end of file
+
+    pub fn set_video_sink(&mut self, video_sink: Box<dyn VideoSink>) {
+        self.cpu.bus_mut().ppu_mut().set_video_sink(video_sink);
+    }
+
+    pub fn framebuffer(&self) -> &[PpuShade] {
+        self.cpu.bus().ppu().framebuffer()
+    }
+
+    pub fn set_audio_sink(&mut self, audio_sink: Box<dyn AudioSink>) {
+        self.cpu.bus_mut().apu_mut().set_audio_sink(audio_sink);
+    }
+
+    pub fn rumble(&self) -> bool {
+        self.cpu.bus().mapper().rumble()
+    }
+
+    pub fn set_tilt(&mut self, x: f32, y: f32) {
+        self.cpu.bus_mut().mapper_mut().set_tilt(x, y);
+    }
+
+    pub fn set_image_source(&mut self, image_source: Box<dyn ImageSource + Send>) {
+        self.cpu.bus_mut().mapper_mut().set_image_source(image_source);
+    }
+
+    pub fn tone(&self) -> bool {
+        self.cpu.bus().mapper().tone()
+    }
+
+    pub fn ir_led(&self) -> bool {
+        self.cpu.bus().mapper().ir_led()
+    }
+
+    pub fn set_ir_input(&mut self, light: bool) {
+        self.cpu.bus_mut().mapper_mut().set_ir_input(light);
+    }
+
+    pub fn serial_output(&self) -> &[u8] {
+        self.cpu.bus().serial_output()
+    }
+
+    pub fn set_button(&mut self, button: Button, pressed: bool) {
+        self.cpu.bus_mut().ppu_mut().controller().set(button, pressed);
+    }
+}
@@ -0,0 +1,15 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Model {
+    DMG,
+    MGB,
+}
+
+impl Model {
+    pub fn from_name(name: &str) -> Option<Model> {
+        match name.to_lowercase().as_str() {
+            "dmg" => Some(Model::DMG),
+            "mgb" | "pocket" => Some(Model::MGB),
+            _ => None,
+        }
+    }
+}
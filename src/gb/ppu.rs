@@ -1,5 +1,5 @@
 use super::controller::Controller;
-use super::video_system::VideoSystem;
+use super::video_sink::{NullVideoSink, VideoSink};
 
 pub const PPU_DISPLAY_WIDTH: usize = 160;
 pub const PPU_DISPLAY_HEIGHT: usize = 144;
@@ -37,6 +37,12 @@ pub struct PpuControl {
     background_enable: bool,
 }
 
+impl Default for PpuControl {
+    fn default() -> PpuControl {
+        PpuControl::new()
+    }
+}
+
 impl PpuControl {
     pub fn new() -> PpuControl {
         PpuControl {
@@ -84,6 +90,12 @@ pub struct PpuStatus {
     coincidence: bool,
 }
 
+impl Default for PpuStatus {
+    fn default() -> PpuStatus {
+        PpuStatus::new()
+    }
+}
+
 impl PpuStatus {
     pub fn new() -> PpuStatus {
         PpuStatus {
@@ -145,6 +157,12 @@ pub struct PpuPalette {
     colour0: PpuShade,
 }
 
+impl Default for PpuPalette {
+    fn default() -> PpuPalette {
+        PpuPalette::new()
+    }
+}
+
 impl PpuPalette {
     pub fn new() -> PpuPalette {
         PpuPalette {
@@ -171,7 +189,7 @@ impl PpuPalette {
 }
 
 pub struct Ppu {
-    video_system: VideoSystem,
+    video_sink: Box<dyn VideoSink>,
     controller: Controller,
 
     framebuffer: Box<[PpuShade]>,
@@ -204,12 +222,20 @@ pub struct Ppu {
 
     vblank: bool,
     stat_interrupt: bool,
+
+    frame: bool,
+}
+
+impl Default for Ppu {
+    fn default() -> Ppu {
+        Ppu::new()
+    }
 }
 
 impl Ppu {
-    pub fn new(video_system: VideoSystem) -> Ppu {
+    pub fn new() -> Ppu {
         Ppu {
-            video_system: video_system,
+            video_sink: Box::new(NullVideoSink),
             controller: Controller::new(),
 
             framebuffer: vec![PpuShade::WHITE; PPU_DISPLAY_WIDTH * PPU_DISPLAY_HEIGHT].into_boxed_slice(),
@@ -242,21 +268,23 @@ impl Ppu {
 
             vblank: false,
             stat_interrupt: false,
+
+            frame: false,
         }
     }
 
     pub fn vram_read(&mut self, address: u16) -> u8 {
         let address = address as usize;
 
-        if address >= 0x8000 && address < 0x9800 {
+        if (0x8000..0x9800).contains(&address) {
             self.latch = self.tile_ram[address - 0x8000];
         }
 
-        else if address >= 0x9800 && address < 0xa000 {
+        else if (0x9800..0xa000).contains(&address) {
             self.latch = self.background_ram[address - 0x9800];
         }
 
-        else if address >= 0xfe00 && address < 0xfea0 {
+        else if (0xfe00..0xfea0).contains(&address) {
             self.latch = self.sprite_oam[address - 0xfe00];
         }
 
@@ -272,15 +300,15 @@ impl Ppu {
 
         self.latch = value;
 
-        if address >= 0x8000 && address < 0x9800 {
+        if (0x8000..0x9800).contains(&address) {
             self.tile_ram[address - 0x8000] = self.latch;
         }
 
-        else if address >= 0x9800 && address < 0xa000 {
+        else if (0x9800..0xa000).contains(&address) {
             self.background_ram[address - 0x9800] = self.latch;
         }
 
-        else if address >= 0xfe00 && address < 0xfea0 {
+        else if (0xfe00..0xfea0).contains(&address) {
             self.sprite_oam[address - 0xfe00] = self.latch;
         }
 
@@ -328,9 +356,8 @@ impl Ppu {
                             self.stat_interrupt = true;
                         }
 
-                        self.video_system.handle_events(&mut self.controller);
-                        self.video_system.render(&self.framebuffer);
-                        self.video_system.sync();
+                        self.video_sink.frame_complete(&self.framebuffer);
+                        self.frame = true;
                     } else {
                         self.mode = PpuMode::OAM;
 
@@ -492,7 +519,7 @@ impl Ppu {
                 let sprite_tile = self.sprite_oam[(i * 4) + 2];
                 let options = self.sprite_oam[(i * 4) + 3];
 
-                if self.sprite_inrange(y) && y >= 0 && y < 144 {
+                if self.sprite_inrange(y) && (0..144).contains(&y) {
                     let palette = match (options & 0x10) != 0 {
                         false => self.sprite_palette_0,
                         true => self.sprite_palette_1,
@@ -528,17 +555,20 @@ impl Ppu {
                             _ => unreachable!()
                         };
 
-                        if sprite_shade != 0 {
-                            if (options & 0x80) == 0 || self.framebuffer[framebuffer_address] == self.background_palette.colour0 {
+                        if sprite_shade != 0
+                            && ((options & 0x80) == 0 || self.framebuffer[framebuffer_address] == self.background_palette.colour0) {
                                 self.framebuffer[framebuffer_address] = pixel_shade;
                             }
-                        }
                     }
                 }
             }
         }
     }
 
+    pub fn set_video_sink(&mut self, video_sink: Box<dyn VideoSink>) {
+        self.video_sink = video_sink;
+    }
+
     pub fn controller(&mut self) -> &mut Controller {
         &mut self.controller
     }
@@ -552,6 +582,19 @@ impl Ppu {
         false
     }
 
+    pub fn get_frame_status(&mut self) -> bool {
+        if self.frame {
+            self.frame = false;
+            return true;
+        }
+
+        false
+    }
+
+    pub fn framebuffer(&self) -> &[PpuShade] {
+        &self.framebuffer
+    }
+
     pub fn get_lcdc_status(&mut self) -> bool {
         if self.stat_interrupt {
             self.stat_interrupt = false;
@@ -613,6 +656,22 @@ impl Ppu {
         self.ly_compare = value;
     }
 
+    pub fn wy_read(&self) -> u8 {
+        self.window_y
+    }
+
+    pub fn wy_write(&mut self, value: u8) {
+        self.window_y = value;
+    }
+
+    pub fn wx_read(&self) -> u8 {
+        self.window_x
+    }
+
+    pub fn wx_write(&mut self, value: u8) {
+        self.window_x = value;
+    }
+
     pub fn bgp_read(&self) -> u8 {
         self.background_palette.read()
     }
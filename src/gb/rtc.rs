@@ -0,0 +1,95 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const RTC_CYCLES_PER_SECOND: usize = 4194304;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RtcMode {
+    HOST,
+    EMULATED,
+}
+
+impl RtcMode {
+    pub fn from_name(name: &str) -> Option<RtcMode> {
+        match name {
+            "host" => Some(RtcMode::HOST),
+            "emulated" => Some(RtcMode::EMULATED),
+            _ => None,
+        }
+    }
+}
+
+pub struct RtcClock {
+    mode: RtcMode,
+    cycles: usize,
+    timestamp: u64,
+}
+
+impl RtcClock {
+    pub fn new() -> RtcClock {
+        RtcClock {
+            mode: RtcMode::HOST,
+            cycles: 0,
+            timestamp: unix_time(),
+        }
+    }
+
+    pub fn get_mode(&self) -> RtcMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: RtcMode) {
+        self.mode = mode;
+        self.cycles = 0;
+        self.timestamp = unix_time();
+    }
+
+    pub fn tick(&mut self, cycles: usize) -> bool {
+        if self.mode == RtcMode::EMULATED {
+            self.cycles += cycles;
+        }
+
+        self.cycles >= RTC_CYCLES_PER_SECOND
+    }
+
+    pub fn elapsed(&mut self) -> u64 {
+        match self.mode {
+            RtcMode::HOST => {
+                let now = unix_time();
+                let elapsed = now.saturating_sub(self.timestamp);
+
+                self.timestamp = now;
+                elapsed
+            },
+            RtcMode::EMULATED => {
+                let elapsed = self.cycles / RTC_CYCLES_PER_SECOND;
+
+                self.cycles %= RTC_CYCLES_PER_SECOND;
+                elapsed as u64
+            },
+        }
+    }
+
+    pub fn reset_subsecond(&mut self) {
+        self.cycles = 0;
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        unix_time()
+    }
+
+    pub fn load_timestamp(&mut self, timestamp: u64) {
+        if self.mode == RtcMode::HOST {
+            self.timestamp = timestamp;
+        }
+    }
+}
+
+impl Default for RtcClock {
+    fn default() -> Self {
+        RtcClock::new()
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}
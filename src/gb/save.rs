@@ -0,0 +1,36 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const SAVE_EXTENSION: &str = "sav";
+pub const SAVE_INTERVAL_FRAMES: usize = 300;
+
+pub fn save_filepath(cartridge_filepath: &Path, save_directory: Option<&Path>) -> PathBuf {
+    let filepath = cartridge_filepath.with_extension(SAVE_EXTENSION);
+
+    match save_directory {
+        Some(directory) => directory.join(filepath.file_name().unwrap_or_default()),
+        None => filepath,
+    }
+}
+
+pub fn read(filepath: &Path) -> io::Result<Option<Vec<u8>>> {
+    match fs::read(filepath) {
+        Ok(data) => Ok(Some(data)),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn write(filepath: &Path, data: &[u8]) -> io::Result<()> {
+    if let Some(directory) = filepath.parent() {
+        if !directory.as_os_str().is_empty() {
+            fs::create_dir_all(directory)?;
+        }
+    }
+
+    let temporary_filepath = filepath.with_extension("sav.tmp");
+
+    fs::write(&temporary_filepath, data)?;
+    fs::rename(&temporary_filepath, filepath)
+}
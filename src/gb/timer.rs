@@ -19,6 +19,12 @@ pub struct Timer {
     interrupt: bool,
 }
 
+impl Default for Timer {
+    fn default() -> Timer {
+        Timer::new()
+    }
+}
+
 impl Timer {
     pub fn new() -> Timer {
         Timer {
@@ -70,6 +76,10 @@ impl Timer {
         }
     }
 
+    pub fn set_divider(&mut self, divider: u16) {
+        self.divider = divider;
+    }
+
     pub fn div_read(&self) -> u8 {
         (self.divider >> 8) as u8
     }
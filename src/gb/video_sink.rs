@@ -0,0 +1,11 @@
+use super::ppu::PpuShade;
+
+pub trait VideoSink {
+    fn frame_complete(&mut self, framebuffer: &[PpuShade]);
+}
+
+pub struct NullVideoSink;
+
+impl VideoSink for NullVideoSink {
+    fn frame_complete(&mut self, _: &[PpuShade]) {}
+}
@@ -4,6 +4,7 @@ use self::registers::{Registers, Flags, Reg16};
 use self::registers::Reg16::{AF, BC, DE, HL, SP};
 
 use super::bus;
+use super::model::Model;
 
 #[derive(Clone, Copy)]
 enum AddressingMode {
@@ -31,17 +32,56 @@ pub struct Z80 {
 impl Z80 {
     pub fn new(bus: bus::Bus) -> Z80 {
         Z80 {
-            bus: bus,
+            bus,
             regs: Registers::new(),
             ime: false,
             halt: false,
         }
     }
 
+    pub fn bus(&self) -> &bus::Bus {
+        &self.bus
+    }
+
+    pub fn bus_mut(&mut self) -> &mut bus::Bus {
+        &mut self.bus
+    }
+
     pub fn reset(&mut self) {
-        self.regs.pc = 0;
-        self.ime = true;
+        self.regs = Registers::new();
+        self.ime = false;
         self.halt = false;
+
+        if !self.bus.has_bootrom() {
+            self.skip_bootrom();
+        }
+    }
+
+    fn skip_bootrom(&mut self) {
+        let mut header_checksum = 0u8;
+
+        for address in 0x134..0x14d {
+            header_checksum = header_checksum.wrapping_sub(self.bus.read(address)).wrapping_sub(1);
+        }
+
+        self.regs.a = match self.bus.get_model() {
+            Model::DMG => 0x01,
+            Model::MGB => 0xff,
+        };
+
+        self.regs.f = Flags::ZERO;
+
+        if header_checksum != 0 {
+            self.regs.f |= Flags::HALFCARRY | Flags::CARRY;
+        }
+
+        self.regs.write16(BC, 0x0013);
+        self.regs.write16(DE, 0x00d8);
+        self.regs.write16(HL, 0x014d);
+        self.regs.sp = 0xfffe;
+        self.regs.pc = 0x0100;
+
+        self.bus.skip_bootrom();
     }
 
     fn interrupt(&mut self, address: u16) {
@@ -1088,7 +1128,6 @@ impl Z80 {
             0xfd => self.cb_set(7, L),
             0xfe => self.cb_set(7, HLP),
             0xff => self.cb_set(7, A),
-            _ => panic!("ERROR: unknown cb instruction 0x{:02x}", opcode)
         }
     }
 
@@ -1129,7 +1168,7 @@ impl Z80 {
     fn alu_rlc(&mut self, mode: AddressingMode) {
         let value = self.read_mode(mode);
 
-        let result = (value << 1) | (value >> 7);
+        let result = value.rotate_left(1);
 
         self.write_mode(mode, result);
 
@@ -1156,7 +1195,7 @@ impl Z80 {
     fn alu_rrc(&mut self, mode: AddressingMode) {
         let value = self.read_mode(mode);
 
-        let result = (value >> 1) | (value << 7);
+        let result = value.rotate_right(1);
 
         self.write_mode(mode, result);
 
@@ -1195,7 +1234,7 @@ impl Z80 {
     fn cb_swap(&mut self, mode: AddressingMode) {
         let value = self.read_mode(mode);
 
-        let result = (value >> 4) | (value << 4);
+        let result = value.rotate_left(4);
 
         self.write_mode(mode, result);
 
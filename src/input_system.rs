@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use sdl2;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+use rgb::{Button, Gameboy};
+
+pub struct InputSystem {
+    event_pump: sdl2::EventPump,
+    keymap: HashMap<Keycode, Button>,
+    tilt_keymap: HashMap<Keycode, (f32, f32)>,
+    tilt: (f32, f32),
+}
+
+impl InputSystem {
+    pub fn new(context: &sdl2::Sdl) -> InputSystem {
+        let mut keymap = HashMap::new();
+
+        keymap.insert(Keycode::A, Button::A);
+        keymap.insert(Keycode::S, Button::B);
+        keymap.insert(Keycode::Z, Button::START);
+        keymap.insert(Keycode::X, Button::SELECT);
+        keymap.insert(Keycode::Up, Button::UP);
+        keymap.insert(Keycode::Down, Button::DOWN);
+        keymap.insert(Keycode::Left, Button::LEFT);
+        keymap.insert(Keycode::Right, Button::RIGHT);
+
+        let mut tilt_keymap = HashMap::new();
+
+        tilt_keymap.insert(Keycode::I, (0.0, -1.0));
+        tilt_keymap.insert(Keycode::K, (0.0, 1.0));
+        tilt_keymap.insert(Keycode::J, (-1.0, 0.0));
+        tilt_keymap.insert(Keycode::L, (1.0, 0.0));
+
+        InputSystem {
+            event_pump: context.event_pump().unwrap(),
+            keymap,
+            tilt_keymap,
+            tilt: (0.0, 0.0),
+        }
+    }
+
+    pub fn handle_events(&mut self, gb: &mut Gameboy) -> bool {
+        for event in self.event_pump.poll_iter() {
+            match event {
+                Event::Quit {..} => return false,
+
+                Event::KeyDown {keycode: Some(keycode), repeat: false, ..} => {
+                    if let Some(&button) = self.keymap.get(&keycode) {
+                        gb.set_button(button, true);
+                    }
+
+                    if let Some(&(x, y)) = self.tilt_keymap.get(&keycode) {
+                        self.tilt = (self.tilt.0 + x, self.tilt.1 + y);
+                        gb.set_tilt(self.tilt.0, self.tilt.1);
+                    }
+                },
+
+                Event::KeyUp {keycode: Some(keycode), ..} => {
+                    if let Some(&button) = self.keymap.get(&keycode) {
+                        gb.set_button(button, false);
+                    }
+
+                    if let Some(&(x, y)) = self.tilt_keymap.get(&keycode) {
+                        self.tilt = (self.tilt.0 - x, self.tilt.1 - y);
+                        gb.set_tilt(self.tilt.0, self.tilt.1);
+                    }
+                },
+
+                _ => {}
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,21 @@
+#![allow(clippy::upper_case_acronyms, clippy::manual_is_multiple_of)]
+
+#[macro_use]
+extern crate bitflags;
+
+pub mod gb;
+
+pub use gb::{EmulationResult, Gameboy};
+pub use gb::apu::Apu;
+pub use gb::audio_sink::{AudioSink, NullAudioSink};
+pub use gb::bus::Bus;
+pub use gb::cartridge::{Cartridge, CartridgeMapper, HeaderValidation};
+pub use gb::controller::Button;
+pub use gb::error::LoadError;
+pub use gb::image_source::{FileSequenceImageSource, ImageSource, StaticImageSource, TestPatternImageSource};
+pub use gb::model::Model;
+pub use gb::ppu::{Ppu, PpuShade, PPU_DISPLAY_WIDTH, PPU_DISPLAY_HEIGHT};
+pub use gb::rtc::RtcMode;
+pub use gb::save::{save_filepath, SAVE_INTERVAL_FRAMES};
+pub use gb::video_sink::{NullVideoSink, VideoSink};
+pub use gb::z80::Z80;
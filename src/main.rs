@@ -1,20 +1,124 @@
-#[macro_use]
-extern crate bitflags;
+#![allow(clippy::upper_case_acronyms, clippy::unnecessary_map_or)]
+
+extern crate rgb;
 extern crate sdl2;
 
-mod gb;
+mod audio_system;
+mod cli;
+mod input_system;
+mod video_system;
 
 use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use rgb::{Cartridge, Gameboy, SAVE_INTERVAL_FRAMES};
+use rgb::gb::image_source;
 
-use gb::Gameboy;
+use audio_system::AudioSystem;
+use cli::{Command, Options};
+use input_system::InputSystem;
+use video_system::VideoSystem;
 
 fn main() {
-    let cartridge_filepath = env::args().nth(1).unwrap();
+    let command = match cli::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            eprintln!("{}", cli::USAGE);
+            process::exit(2);
+        },
+    };
+
+    match command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::Info(cartridge_filepath) => info(&cartridge_filepath),
+        Command::Run(options) => run(&options),
+    }
+}
+
+fn fail(filepath: &str, e: &dyn fmt::Display) -> ! {
+    eprintln!("ERROR: {}: {}", filepath, e);
+    process::exit(1);
+}
+
+fn info(cartridge_filepath: &str) {
+    match Cartridge::new(cartridge_filepath) {
+        Ok(cartridge) => cartridge.info(),
+        Err(e) => fail(cartridge_filepath, &e),
+    }
+}
+
+fn run(options: &Options) {
+    let mut cartridge = Cartridge::new(&options.cartridge_filepath).unwrap_or_else(|e| fail(&options.cartridge_filepath, &e));
+
+    if let Some(mapper) = options.mapper {
+        cartridge.set_mapper(mapper);
+    }
+
+    let mut gb = Gameboy::from_cartridge(cartridge).unwrap_or_else(|e| fail(&options.cartridge_filepath, &e));
+
+    gb.set_model(options.model);
+
+    if let Some(ref bootrom_filepath) = options.bootrom_filepath {
+        let bootrom = fs::read(bootrom_filepath).unwrap_or_else(|e| fail(bootrom_filepath, &e));
+        gb.set_bootrom(Some(bootrom)).unwrap_or_else(|e| fail(bootrom_filepath, &e));
+    }
+
+    gb.set_rtc_mode(options.rtc_mode);
+
+    if let Some(ref camera_source) = options.camera_source {
+        let image_source = image_source::open(camera_source).unwrap_or_else(|e| fail(camera_source, &e));
+        gb.set_image_source(image_source);
+    }
+
+    let save_filepath = rgb::save_filepath(Path::new(&options.cartridge_filepath), options.save_directory.as_ref().map(Path::new));
+
+    if !options.no_save {
+        gb.load_save(&save_filepath).unwrap_or_else(|e| fail(&save_filepath.to_string_lossy(), &e));
+    }
+
+    let mut input_system = None;
+
+    if !options.headless {
+        let sdl_context = sdl2::init().unwrap();
+
+        if !options.mute {
+            gb.set_audio_sink(Box::new(AudioSystem::new(&sdl_context)));
+        }
+
+        let video_system = VideoSystem::new(&sdl_context, "rgb", options.scale, options.fullscreen, options.palette);
+        gb.set_video_sink(Box::new(video_system));
+
+        input_system = Some(InputSystem::new(&sdl_context));
+    }
 
-    let mut gb = Gameboy::new(&cartridge_filepath);
     gb.reset();
 
-    loop {
-        gb.run();
+    let mut frames = 0;
+
+    while options.frame_limit.map_or(true, |frame_limit| frames < frame_limit) {
+        gb.run_frame();
+        frames += 1;
+
+        if frames % SAVE_INTERVAL_FRAMES == 0 {
+            flush_save(&mut gb, &save_filepath);
+        }
+
+        if let Some(ref mut input_system) = input_system {
+            if !input_system.handle_events(&mut gb) {
+                break;
+            }
+        }
+    }
+
+    flush_save(&mut gb, &save_filepath);
+}
+
+fn flush_save(gb: &mut Gameboy, save_filepath: &Path) {
+    if let Err(e) = gb.flush_save() {
+        eprintln!("ERROR: {}: {}", save_filepath.display(), e);
     }
 }
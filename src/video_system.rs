@@ -0,0 +1,134 @@
+use std::time::{Duration, Instant};
+use std::thread;
+
+use sdl2;
+use sdl2::pixels::PixelFormatEnum;
+
+use rgb::{PpuShade, VideoSink, PPU_DISPLAY_WIDTH, PPU_DISPLAY_HEIGHT};
+
+pub const FRAME_TIME: f64 = (1.0 / 59.73) * 1000.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Palette {
+    GREY,
+    GREEN,
+    POCKET,
+}
+
+impl Palette {
+    pub fn from_name(name: &str) -> Option<Palette> {
+        match name {
+            "grey" | "gray" => Some(Palette::GREY),
+            "green" | "dmg" => Some(Palette::GREEN),
+            "pocket" | "mgb" => Some(Palette::POCKET),
+            _ => None,
+        }
+    }
+
+    fn colour(self, shade: PpuShade) -> [u8; 3] {
+        match (self, shade) {
+            (Palette::GREY, PpuShade::WHITE) => [0xff, 0xff, 0xff],
+            (Palette::GREY, PpuShade::LIGHT) => [0xaa, 0xaa, 0xaa],
+            (Palette::GREY, PpuShade::DARK)  => [0x55, 0x55, 0x55],
+            (Palette::GREY, PpuShade::BLACK) => [0x00, 0x00, 0x00],
+
+            (Palette::GREEN, PpuShade::WHITE) => [0x9b, 0xbc, 0x0f],
+            (Palette::GREEN, PpuShade::LIGHT) => [0x8b, 0xac, 0x0f],
+            (Palette::GREEN, PpuShade::DARK)  => [0x30, 0x62, 0x30],
+            (Palette::GREEN, PpuShade::BLACK) => [0x0f, 0x38, 0x0f],
+
+            (Palette::POCKET, PpuShade::WHITE) => [0xc4, 0xcf, 0xa1],
+            (Palette::POCKET, PpuShade::LIGHT) => [0x8b, 0x95, 0x6d],
+            (Palette::POCKET, PpuShade::DARK)  => [0x4d, 0x53, 0x3c],
+            (Palette::POCKET, PpuShade::BLACK) => [0x1f, 0x1f, 0x1f],
+        }
+    }
+}
+
+pub struct VideoSystem {
+    canvas: sdl2::render::WindowCanvas,
+    texture_creator: sdl2::render::TextureCreator<sdl2::video::WindowContext>,
+
+    palette: Palette,
+
+    last_time: Instant,
+}
+
+impl VideoSystem {
+    pub fn new(context: &sdl2::Sdl, title: &str, scale: u32, fullscreen: bool, palette: Palette) -> VideoSystem {
+        let width = PPU_DISPLAY_WIDTH as u32;
+        let height = PPU_DISPLAY_HEIGHT as u32;
+
+        let video_subsystem = context.video().unwrap();
+        let mut window_builder = video_subsystem.window(title, width * scale, height * scale);
+        window_builder.position_centered();
+
+        if fullscreen {
+            window_builder.fullscreen_desktop();
+        }
+
+        let window = window_builder.build().unwrap();
+        let mut canvas = window.into_canvas().build().unwrap();
+        canvas.set_logical_size(width, height).unwrap();
+
+        let texture_creator = canvas.texture_creator();
+
+        VideoSystem {
+            canvas,
+            texture_creator,
+
+            palette,
+
+            last_time: Instant::now(),
+        }
+    }
+
+    pub fn render(&mut self, framebuffer: &[PpuShade]) {
+        let palette = self.palette;
+        let mut texture = self.texture_creator.create_texture_streaming(PixelFormatEnum::RGB24, PPU_DISPLAY_WIDTH as u32, PPU_DISPLAY_HEIGHT as u32).unwrap();
+
+        self.canvas.clear();
+
+        texture.with_lock(None, |buffer: &mut [u8], pitch: usize| {
+            for y in 0..144 {
+                for x in 0..160 {
+                    let texture_address = (y * pitch) + (x * 3);
+                    let framebuffer_address = (y * 160) + x;
+
+                    let pixel_colour = palette.colour(framebuffer[framebuffer_address]);
+
+                    buffer[texture_address]     = pixel_colour[0];
+                    buffer[texture_address + 1] = pixel_colour[1];
+                    buffer[texture_address + 2] = pixel_colour[2];
+                }
+            }
+        }).unwrap();
+
+        self.canvas.copy(&texture, None, None).unwrap();
+        self.canvas.present();
+    }
+
+    pub fn sync(&mut self) {
+        let current_time = Instant::now();
+        
+        let elapsed = current_time.duration_since(self.last_time);
+		let elapsed_ms = (elapsed.as_secs() as f64 * 1000.0) + (elapsed.subsec_nanos() as f64 / 1000000.0);
+
+        if elapsed_ms < FRAME_TIME {
+            let sleep_time = (FRAME_TIME - elapsed_ms) as u64;
+
+            if sleep_time != 0 {
+                thread::sleep(Duration::from_millis(sleep_time));
+            }
+        }
+
+        self.last_time = Instant::now();
+    }
+}
+
+impl VideoSink for VideoSystem {
+    fn frame_complete(&mut self, framebuffer: &[PpuShade]) {
+        self.render(framebuffer);
+        self.sync();
+    }
+}